@@ -0,0 +1,308 @@
+use std::path::Path;
+use tokio::process::Command;
+
+use super::journal::{begin_pending, discard_pending, finish_pending};
+use super::merge::{conflicted_paths, head_oid, MergeResult};
+
+/// Runs `git cherry-pick` or `git revert` and reports a stop on conflicts the
+/// same way `merge_git_branch` does.
+async fn run_sequencer(
+    repo_path: &str,
+    operation: &str,
+    args: &[String],
+    success_status: &str,
+) -> Result<MergeResult, String> {
+    let repo = Path::new(repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let head_before = head_oid(repo).await;
+    let action = args.first().map(String::as_str);
+    let starting = !matches!(action, Some("--continue") | Some("--abort"));
+
+    if starting {
+        begin_pending(repo, operation).await;
+    }
+
+    let output = Command::new("git")
+        .arg(operation)
+        .args(args)
+        .env("LC_ALL", "C")
+        .env("GIT_EDITOR", "true")
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git {}: {}", operation, e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    if !output.status.success() {
+        let conflicts = conflicted_paths(repo).await?;
+        if conflicts.is_empty() {
+            if starting {
+                discard_pending(repo, operation);
+            }
+            return Err(format!("Git {} failed: {}{}", operation, stderr, stdout));
+        }
+
+        return Ok(MergeResult {
+            status: "conflict".to_string(),
+            head: head_oid(repo).await.or(head_before),
+            conflicts,
+            message: stdout.trim().to_string(),
+        });
+    }
+
+    if action == Some("--abort") {
+        discard_pending(repo, operation);
+    } else {
+        finish_pending(repo, operation).await;
+    }
+
+    Ok(MergeResult {
+        status: success_status.to_string(),
+        head: head_oid(repo).await,
+        conflicts: Vec::new(),
+        message: stdout.trim().to_string(),
+    })
+}
+
+fn commit_args(
+    hashes: &[String],
+    no_commit: bool,
+    mainline: Option<u32>,
+) -> Result<Vec<String>, String> {
+    let hashes: Vec<String> = hashes
+        .iter()
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+        .collect();
+
+    if hashes.is_empty() {
+        return Err("No commits selected".to_string());
+    }
+
+    let mut args = Vec::new();
+
+    if no_commit {
+        args.push("--no-commit".to_string());
+    }
+
+    if let Some(parent) = mainline {
+        args.push("--mainline".to_string());
+        args.push(parent.to_string());
+    }
+
+    args.push("--end-of-options".to_string());
+    args.extend(hashes);
+    Ok(args)
+}
+
+/// Applies `hashes` on top of HEAD in the given order.
+#[tauri::command]
+pub async fn cherry_pick_commits(
+    repo_path: String,
+    hashes: Vec<String>,
+    record_origin: Option<bool>,
+    no_commit: Option<bool>,
+    mainline: Option<u32>,
+) -> Result<MergeResult, String> {
+    let no_commit = no_commit.unwrap_or(false);
+    let mut args = Vec::new();
+
+    if record_origin.unwrap_or(false) {
+        args.push("-x".to_string());
+    }
+
+    args.extend(commit_args(&hashes, no_commit, mainline)?);
+
+    let status = if no_commit { "applied" } else { "picked" };
+    run_sequencer(&repo_path, "cherry-pick", &args, status).await
+}
+
+/// Reverts `hashes` in the given order, newest first being the usual choice.
+#[tauri::command]
+pub async fn revert_commits(
+    repo_path: String,
+    hashes: Vec<String>,
+    no_commit: Option<bool>,
+    mainline: Option<u32>,
+) -> Result<MergeResult, String> {
+    let no_commit = no_commit.unwrap_or(false);
+    let mut args = vec!["--no-edit".to_string()];
+    args.extend(commit_args(&hashes, no_commit, mainline)?);
+
+    let status = if no_commit { "applied" } else { "reverted" };
+    run_sequencer(&repo_path, "revert", &args, status).await
+}
+
+#[tauri::command]
+pub async fn cherry_pick_continue(repo_path: String) -> Result<MergeResult, String> {
+    run_sequencer(
+        &repo_path,
+        "cherry-pick",
+        &["--continue".to_string()],
+        "picked",
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn cherry_pick_abort(repo_path: String) -> Result<(), String> {
+    run_sequencer(
+        &repo_path,
+        "cherry-pick",
+        &["--abort".to_string()],
+        "aborted",
+    )
+    .await
+    .map(|_| ())
+}
+
+#[tauri::command]
+pub async fn revert_continue(repo_path: String) -> Result<MergeResult, String> {
+    run_sequencer(
+        &repo_path,
+        "revert",
+        &["--continue".to_string()],
+        "reverted",
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn revert_abort(repo_path: String) -> Result<(), String> {
+    run_sequencer(&repo_path, "revert", &["--abort".to_string()], "aborted")
+        .await
+        .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_util::{commit_file, git, init_repo, path_str, write_file};
+    use tempfile::TempDir;
+
+    /// A repository on `main` with a `feature` branch that edits `a.txt`
+    /// and then adds `b.txt`. `main` edits `a.txt` too when `conflict` is set.
+    fn repo_with_feature(conflict: bool) -> TempDir {
+        let repo = init_repo();
+        commit_file(repo.path(), "a.txt", "base\n", "base");
+        git(repo.path(), &["switch", "-q", "-c", "feature"]);
+        commit_file(repo.path(), "a.txt", "feature\n", "feature edit");
+        commit_file(repo.path(), "b.txt", "b\n", "feature add");
+        git(repo.path(), &["switch", "-q", "main"]);
+        if conflict {
+            commit_file(repo.path(), "a.txt", "main\n", "main edit");
+        }
+        repo
+    }
+
+    async fn pick(repo: &TempDir, revs: &[&str]) -> MergeResult {
+        let hashes = revs.iter().map(|rev| git(repo.path(), &["rev-parse", rev]));
+        cherry_pick_commits(path_str(repo), hashes.collect(), Some(true), None, None)
+            .await
+            .unwrap_or_else(|e| panic!("cherry-pick failed: {}", e))
+    }
+
+    #[tokio::test]
+    async fn picks_commits_in_order() {
+        let repo = repo_with_feature(false);
+        let picked = git(repo.path(), &["rev-parse", "feature"]);
+
+        let result = pick(&repo, &["feature~1", "feature"]).await;
+
+        assert_eq!(result.status, "picked");
+        assert_eq!(
+            git(repo.path(), &["log", "--format=%s", "-2"]),
+            "feature add\nfeature edit"
+        );
+        let body = git(repo.path(), &["log", "--format=%b", "-1"]);
+        assert!(body.contains(&format!("cherry picked from commit {}", picked)));
+    }
+
+    #[tokio::test]
+    async fn continues_a_pick_after_a_conflict() {
+        let repo = repo_with_feature(true);
+        let head = git(repo.path(), &["rev-parse", "HEAD"]);
+
+        let result = pick(&repo, &["feature~1", "feature"]).await;
+
+        assert_eq!(result.status, "conflict");
+        assert_eq!(result.head.as_deref(), Some(head.as_str()));
+        assert_eq!(result.conflicts, ["a.txt"]);
+
+        write_file(repo.path(), "a.txt", "resolved\n");
+        git(repo.path(), &["add", "a.txt"]);
+        let result = cherry_pick_continue(path_str(&repo)).await.unwrap();
+
+        assert_eq!(result.status, "picked");
+        assert_eq!(
+            git(repo.path(), &["log", "--format=%s", "-3"]),
+            "feature add\nfeature edit\nmain edit"
+        );
+        assert_eq!(git(repo.path(), &["show", "HEAD~1:a.txt"]), "resolved");
+    }
+
+    #[tokio::test]
+    async fn abort_restores_head() {
+        let repo = repo_with_feature(true);
+        let head = git(repo.path(), &["rev-parse", "HEAD"]);
+        pick(&repo, &["feature~1"]).await;
+
+        cherry_pick_abort(path_str(&repo)).await.unwrap();
+
+        assert_eq!(git(repo.path(), &["rev-parse", "HEAD"]), head);
+        assert_eq!(git(repo.path(), &["status", "--porcelain"]), "");
+    }
+
+    #[tokio::test]
+    async fn reverts_a_merge_against_its_mainline() {
+        let repo = repo_with_feature(false);
+        commit_file(repo.path(), "c.txt", "c\n", "main add");
+        git(
+            repo.path(),
+            &["merge", "-q", "--no-ff", "--no-edit", "feature"],
+        );
+        let merge = git(repo.path(), &["rev-parse", "HEAD"]);
+
+        let without_mainline =
+            revert_commits(path_str(&repo), vec![merge.clone()], None, None).await;
+        assert!(matches!(without_mainline, Err(e) if e.contains("no -m option was given")));
+
+        let result = revert_commits(path_str(&repo), vec![merge], None, Some(1))
+            .await
+            .unwrap_or_else(|e| panic!("revert failed: {}", e));
+
+        assert_eq!(result.status, "reverted");
+        assert_eq!(git(repo.path(), &["show", "HEAD:a.txt"]), "base");
+        assert!(!repo.path().join("b.txt").exists());
+        assert!(repo.path().join("c.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn hashes_are_never_parsed_as_options() {
+        let repo = repo_with_feature(false);
+        let head = git(repo.path(), &["rev-parse", "HEAD"]);
+
+        let result = cherry_pick_commits(
+            path_str(&repo),
+            vec!["--quit".to_string()],
+            None,
+            None,
+            None,
+        )
+        .await;
+
+        assert!(matches!(result, Err(e) if e.starts_with("Git cherry-pick failed")));
+        assert_eq!(git(repo.path(), &["rev-parse", "HEAD"]), head);
+    }
+}
@@ -0,0 +1,730 @@
+use std::path::Path;
+use tokio::process::Command;
+
+use super::patch::unified_arg;
+
+#[derive(serde::Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffLineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+#[derive(serde::Serialize)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub content: String,
+    pub old_line: Option<u32>,
+    pub new_line: Option<u32>,
+    /// Set when git printed `\ No newline at end of file` after this line.
+    pub no_newline: bool,
+}
+
+#[derive(serde::Serialize)]
+pub struct DiffHunk {
+    pub header: String,
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    pub section: String,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct FileDiff {
+    pub status: String,
+    pub old_path: Option<String>,
+    pub new_path: Option<String>,
+    pub old_mode: Option<String>,
+    pub new_mode: Option<String>,
+    pub similarity: Option<u32>,
+    pub binary: bool,
+    pub additions: u32,
+    pub deletions: u32,
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// Arguments that pin down the patch format regardless of user config, so
+/// `parse_unified_diff` always sees `a/` and `b/` prefixes.
+pub(crate) const PATCH_FORMAT_ARGS: [&str; 4] = [
+    "--no-color",
+    "--no-ext-diff",
+    "--src-prefix=a/",
+    "--dst-prefix=b/",
+];
+
+/// Undoes git's C-style quoting of paths with special characters.
+pub(crate) fn unquote_path(path: &str) -> String {
+    let Some(inner) = path.strip_prefix('"').and_then(|p| p.strip_suffix('"')) else {
+        return path.to_string();
+    };
+
+    let mut bytes = Vec::with_capacity(inner.len());
+    let mut chars = inner.bytes().peekable();
+
+    while let Some(byte) = chars.next() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+
+        match chars.next() {
+            Some(b'n') => bytes.push(b'\n'),
+            Some(b't') => bytes.push(b'\t'),
+            Some(b'r') => bytes.push(b'\r'),
+            Some(b'a') => bytes.push(0x07),
+            Some(b'b') => bytes.push(0x08),
+            Some(b'f') => bytes.push(0x0c),
+            Some(b'v') => bytes.push(0x0b),
+            Some(digit @ b'0'..=b'7') => {
+                let mut value = u32::from(digit - b'0');
+                for _ in 0..2 {
+                    match chars.peek() {
+                        Some(d @ b'0'..=b'7') => {
+                            value = value * 8 + u32::from(d - b'0');
+                            chars.next();
+                        }
+                        _ => break,
+                    }
+                }
+                bytes.push(value as u8);
+            }
+            Some(other) => bytes.push(other),
+            None => bytes.push(b'\\'),
+        }
+    }
+
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+/// Reads the path from a `---`/`+++` line, or `None` for `/dev/null`.
+fn header_path(value: &str) -> Option<String> {
+    // Git appends a tab after paths containing spaces.
+    let value = value.strip_suffix('\t').unwrap_or(value);
+    if value == "/dev/null" {
+        return None;
+    }
+
+    let path = unquote_path(value);
+    Some(path.get(2..).map(String::from).unwrap_or(path))
+}
+
+/// Splits the `a/x b/x` part of a `diff --git` line. Only used for files
+/// without `---`/`+++` lines, where both paths are the same unless a
+/// rename header follows.
+fn git_header_paths(rest: &str) -> Option<(String, String)> {
+    if rest.starts_with('"') {
+        // The old path ends at the first quote that is not escaped.
+        let mut escaped = false;
+        let (end, _) = rest.char_indices().skip(1).find(|&(_, c)| {
+            let closing = c == '"' && !escaped;
+            escaped = c == '\\' && !escaped;
+            closing
+        })?;
+        let old = unquote_path(&rest[..=end]);
+        let new = unquote_path(rest[end + 1..].trim());
+        return Some((old.get(2..)?.to_string(), new.get(2..)?.to_string()));
+    }
+
+    let half = rest.len().checked_sub(1)? / 2;
+    let (old, new) = (rest.get(..half)?, rest.get(half + 1..)?);
+    let (old, new) = (old.get(2..)?, new.get(2..)?);
+    (old == new).then(|| (old.to_string(), new.to_string()))
+}
+
+fn parse_range(range: &str) -> Option<(u32, u32)> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+fn parse_hunk_header(line: &str) -> Option<DiffHunk> {
+    let rest = line.strip_prefix("@@ -")?;
+    let (ranges, section) = rest.split_once(" @@")?;
+    let (old_range, new_range) = ranges.split_once(" +")?;
+    let (old_start, old_lines) = parse_range(old_range)?;
+    let (new_start, new_lines) = parse_range(new_range)?;
+
+    Some(DiffHunk {
+        header: line.to_string(),
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        section: section.trim().to_string(),
+        lines: Vec::new(),
+    })
+}
+
+/// Parses `git diff` patch output into files, hunks and numbered lines.
+pub(crate) fn parse_unified_diff(patch: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    // Lines still expected in the current hunk, from its header counts.
+    let mut old_left: u32 = 0;
+    let mut new_left: u32 = 0;
+    let mut old_line = 0;
+    let mut new_line = 0;
+
+    for line in patch.lines() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            let (old_path, new_path) = git_header_paths(rest).unzip();
+            files.push(FileDiff {
+                status: "modified".to_string(),
+                old_path,
+                new_path,
+                ..Default::default()
+            });
+            old_left = 0;
+            new_left = 0;
+            continue;
+        }
+
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+
+        if old_left > 0 || new_left > 0 {
+            let (kind, content) = match line.split_at_checked(1) {
+                Some(("+", content)) => (DiffLineKind::Added, content),
+                Some(("-", content)) => (DiffLineKind::Removed, content),
+                Some((" ", content)) => (DiffLineKind::Context, content),
+                Some(("\\", _)) => {
+                    if let Some(last) = file.hunks.last_mut().and_then(|h| h.lines.last_mut()) {
+                        last.no_newline = true;
+                    }
+                    continue;
+                }
+                // Some tools strip the space of empty context lines.
+                _ => (DiffLineKind::Context, ""),
+            };
+
+            let mut entry = DiffLine {
+                kind,
+                content: content.to_string(),
+                old_line: None,
+                new_line: None,
+                no_newline: false,
+            };
+
+            if kind != DiffLineKind::Added {
+                old_line += 1;
+                old_left = old_left.saturating_sub(1);
+                entry.old_line = Some(old_line);
+            }
+            if kind != DiffLineKind::Removed {
+                new_line += 1;
+                new_left = new_left.saturating_sub(1);
+                entry.new_line = Some(new_line);
+            }
+
+            match kind {
+                DiffLineKind::Added => file.additions += 1,
+                DiffLineKind::Removed => file.deletions += 1,
+                DiffLineKind::Context => {}
+            }
+
+            if let Some(hunk) = file.hunks.last_mut() {
+                hunk.lines.push(entry);
+            }
+            continue;
+        }
+
+        // A marker can also follow the last line of a hunk.
+        if line.starts_with('\\') {
+            if let Some(last) = file.hunks.last_mut().and_then(|h| h.lines.last_mut()) {
+                last.no_newline = true;
+            }
+        } else if let Some(hunk) = parse_hunk_header(line) {
+            old_left = hunk.old_lines;
+            new_left = hunk.new_lines;
+            old_line = hunk.old_start.saturating_sub(1);
+            new_line = hunk.new_start.saturating_sub(1);
+            file.hunks.push(hunk);
+        } else if let Some(path) = line.strip_prefix("--- ") {
+            file.old_path = header_path(path);
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            file.new_path = header_path(path);
+        } else if let Some(mode) = line.strip_prefix("new file mode ") {
+            file.status = "added".to_string();
+            file.old_path = None;
+            file.new_mode = Some(mode.to_string());
+        } else if let Some(mode) = line.strip_prefix("deleted file mode ") {
+            file.status = "deleted".to_string();
+            file.new_path = None;
+            file.old_mode = Some(mode.to_string());
+        } else if let Some(mode) = line.strip_prefix("old mode ") {
+            file.old_mode = Some(mode.to_string());
+        } else if let Some(mode) = line.strip_prefix("new mode ") {
+            file.new_mode = Some(mode.to_string());
+        } else if let Some(path) = line.strip_prefix("rename from ") {
+            file.status = "renamed".to_string();
+            file.old_path = Some(unquote_path(path));
+        } else if let Some(path) = line.strip_prefix("rename to ") {
+            file.new_path = Some(unquote_path(path));
+        } else if let Some(path) = line.strip_prefix("copy from ") {
+            file.status = "copied".to_string();
+            file.old_path = Some(unquote_path(path));
+        } else if let Some(path) = line.strip_prefix("copy to ") {
+            file.new_path = Some(unquote_path(path));
+        } else if let Some(score) = line.strip_prefix("similarity index ") {
+            file.similarity = score.trim_end_matches('%').parse().ok();
+        } else if let Some(index) = line.strip_prefix("index ") {
+            // `index abc..def 100644` carries the mode when it is unchanged.
+            if let Some((_, mode)) = index.split_once(' ') {
+                file.old_mode.get_or_insert_with(|| mode.to_string());
+                file.new_mode.get_or_insert_with(|| mode.to_string());
+            }
+        } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+            file.binary = true;
+        }
+    }
+
+    files
+}
+
+/// One side of a diff: the working tree, the index or any commit-ish.
+#[derive(Clone, serde::Deserialize)]
+#[serde(tag = "kind", content = "rev", rename_all = "lowercase")]
+pub enum DiffSide {
+    Worktree,
+    Index,
+    Commit(String),
+}
+
+/// Maps a source/target pair onto `git diff` arguments. Git only compares
+/// commit → index → worktree in that direction, so the other orders are
+/// expressed with `-R`.
+fn side_args(source: &DiffSide, target: &DiffSide) -> Result<Vec<String>, String> {
+    let args: Vec<&str> = match (source, target) {
+        (DiffSide::Commit(from), DiffSide::Commit(to)) => {
+            vec!["--end-of-options", from, to]
+        }
+        (DiffSide::Commit(rev), DiffSide::Index) => vec!["--cached", "--end-of-options", rev],
+        (DiffSide::Commit(rev), DiffSide::Worktree) => vec!["--end-of-options", rev],
+        (DiffSide::Index, DiffSide::Worktree) => vec![],
+        (DiffSide::Index, DiffSide::Commit(rev)) => {
+            vec!["-R", "--cached", "--end-of-options", rev]
+        }
+        (DiffSide::Worktree, DiffSide::Commit(rev)) => vec!["-R", "--end-of-options", rev],
+        (DiffSide::Worktree, DiffSide::Index) => vec!["-R"],
+        (DiffSide::Index, DiffSide::Index) | (DiffSide::Worktree, DiffSide::Worktree) => {
+            return Err("Source and target must differ".to_string());
+        }
+    };
+
+    Ok(args.into_iter().map(String::from).collect())
+}
+
+/// Pathspecs for single-file mode. Passing the old path of a rename as well
+/// lets git pair both sides instead of showing an addition.
+fn pathspecs(repo: &Path, file_path: Option<String>, old_file_path: Option<String>) -> Vec<String> {
+    [file_path, old_file_path]
+        .into_iter()
+        .flatten()
+        .map(|path| {
+            Path::new(&path)
+                .strip_prefix(repo)
+                .ok()
+                .and_then(|p| p.to_str())
+                .map(String::from)
+                .unwrap_or(path)
+        })
+        .collect()
+}
+
+async fn run_diff(repo: &Path, command: &str, args: Vec<String>) -> Result<Vec<FileDiff>, String> {
+    let output = Command::new("git")
+        .arg("--no-pager")
+        .arg(command)
+        .args(PATCH_FORMAT_ARGS)
+        .args(&args)
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git {}: {}", command, e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git {} command failed: {}", command, stderr));
+    }
+
+    Ok(parse_unified_diff(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[tauri::command]
+pub async fn get_git_revision_diff(
+    repo_path: String,
+    source: DiffSide,
+    target: DiffSide,
+    file_path: Option<String>,
+    old_file_path: Option<String>,
+    context_lines: Option<u32>,
+) -> Result<Vec<FileDiff>, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let mut args = vec!["--find-renames".to_string(), unified_arg(context_lines)];
+    args.extend(side_args(&source, &target)?);
+    args.push("--".to_string());
+    args.extend(pathspecs(repo, file_path, old_file_path));
+
+    run_diff(repo, "diff", args).await
+}
+
+/// Shows what `commit_hash` changed relative to its first parent. Root
+/// commits are shown against the empty tree.
+#[tauri::command]
+pub async fn get_git_commit_diff(
+    repo_path: String,
+    commit_hash: String,
+    file_path: Option<String>,
+    old_file_path: Option<String>,
+    context_lines: Option<u32>,
+) -> Result<Vec<FileDiff>, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let mut args = vec![
+        "--format=".to_string(),
+        "--find-renames".to_string(),
+        "--diff-merges=first-parent".to_string(),
+        unified_arg(context_lines),
+        "--end-of-options".to_string(),
+        commit_hash,
+        "--".to_string(),
+    ];
+    args.extend(pathspecs(repo, file_path, old_file_path));
+
+    run_diff(repo, "show", args).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_util::{commit_file, git, init_repo, path_str, write_file};
+    use tempfile::TempDir;
+
+    /// `(marker, content, old line, new line, no newline)`.
+    type Line<'a> = (char, &'a str, Option<u32>, Option<u32>, bool);
+
+    fn lines(hunk: &DiffHunk) -> Vec<Line<'_>> {
+        hunk.lines
+            .iter()
+            .map(|l| {
+                let marker = match l.kind {
+                    DiffLineKind::Context => ' ',
+                    DiffLineKind::Added => '+',
+                    DiffLineKind::Removed => '-',
+                };
+                (
+                    marker,
+                    l.content.as_str(),
+                    l.old_line,
+                    l.new_line,
+                    l.no_newline,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_added_and_deleted_files_against_dev_null() {
+        let files = parse_unified_diff(
+            "diff --git a/added.txt b/added.txt\n\
+             new file mode 100644\n\
+             index 0000000..92d5444\n\
+             --- /dev/null\n\
+             +++ b/added.txt\n\
+             @@ -0,0 +1 @@\n\
+             +fresh\n\
+             diff --git a/del.txt b/del.txt\n\
+             deleted file mode 100644\n\
+             index 286c5f5..0000000\n\
+             --- a/del.txt\n\
+             +++ /dev/null\n\
+             @@ -1 +0,0 @@\n\
+             -gone\n",
+        );
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].status, "added");
+        assert_eq!(files[0].old_path, None);
+        assert_eq!(files[0].new_path.as_deref(), Some("added.txt"));
+        assert_eq!(files[0].old_mode, None);
+        assert_eq!(files[0].new_mode.as_deref(), Some("100644"));
+        assert_eq!(files[0].additions, 1);
+        assert_eq!(
+            lines(&files[0].hunks[0]),
+            [('+', "fresh", None, Some(1), false)]
+        );
+
+        assert_eq!(files[1].status, "deleted");
+        assert_eq!(files[1].old_path.as_deref(), Some("del.txt"));
+        assert_eq!(files[1].new_path, None);
+        assert_eq!(files[1].old_mode.as_deref(), Some("100644"));
+        assert_eq!(files[1].deletions, 1);
+        assert_eq!(
+            lines(&files[1].hunks[0]),
+            [('-', "gone", Some(1), None, false)]
+        );
+    }
+
+    #[test]
+    fn marks_lines_without_a_trailing_newline() {
+        let files = parse_unified_diff(
+            "diff --git a/nonl.txt b/nonl.txt\n\
+             index 54d55bf..2f43848 100644\n\
+             --- a/nonl.txt\n\
+             +++ b/nonl.txt\n\
+             @@ -1,3 +1,3 @@\n\
+             \x20one\n\
+             \x20two\n\
+             -three\n\
+             \\ No newline at end of file\n\
+             +THREE\n\
+             diff --git a/r1.txt b/r2.txt\n\
+             similarity index 83%\n\
+             rename from r1.txt\n\
+             rename to r2.txt\n\
+             index 0fdf397..85e4062 100644\n\
+             --- a/r1.txt\n\
+             +++ b/r2.txt\n\
+             @@ -3,4 +3,4 @@ b\n\
+             \x20c\n\
+             \x20d\n\
+             \x20e\n\
+             -f\n\
+             +F\n\
+             \\ No newline at end of file\n",
+        );
+
+        assert_eq!(
+            lines(&files[0].hunks[0]),
+            [
+                (' ', "one", Some(1), Some(1), false),
+                (' ', "two", Some(2), Some(2), false),
+                ('-', "three", Some(3), None, true),
+                ('+', "THREE", None, Some(3), false),
+            ]
+        );
+
+        let renamed = &files[1];
+        assert_eq!(renamed.status, "renamed");
+        assert_eq!(renamed.old_path.as_deref(), Some("r1.txt"));
+        assert_eq!(renamed.new_path.as_deref(), Some("r2.txt"));
+        assert_eq!(renamed.similarity, Some(83));
+        assert_eq!(renamed.hunks[0].section, "b");
+        assert_eq!(renamed.hunks[0].old_start, 3);
+        assert_eq!(
+            lines(&renamed.hunks[0])[3..],
+            [
+                ('-', "f", Some(6), None, false),
+                ('+', "F", None, Some(6), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_binary_mode_only_and_pure_rename_headers() {
+        let files = parse_unified_diff(
+            "diff --git a/data.bin b/data.bin\n\
+             index 88768ef..3e3315e 100644\n\
+             Binary files a/data.bin and b/data.bin differ\n\
+             diff --git a/img.png b/img.png\n\
+             deleted file mode 100644\n\
+             index 47a1e10..0000000\n\
+             Binary files a/img.png and /dev/null differ\n\
+             diff --git a/mode.sh b/mode.sh\n\
+             old mode 100644\n\
+             new mode 100755\n\
+             diff --git a/old.txt b/new.txt\n\
+             similarity index 100%\n\
+             rename from old.txt\n\
+             rename to new.txt\n",
+        );
+
+        assert_eq!(files.len(), 4);
+
+        assert!(files[0].binary);
+        assert_eq!(files[0].status, "modified");
+        assert_eq!(files[0].new_path.as_deref(), Some("data.bin"));
+        assert_eq!(files[0].old_mode.as_deref(), Some("100644"));
+        assert!(files[0].hunks.is_empty());
+
+        assert!(files[1].binary);
+        assert_eq!(files[1].status, "deleted");
+        assert_eq!(files[1].old_path.as_deref(), Some("img.png"));
+        assert_eq!(files[1].new_path, None);
+
+        assert!(!files[2].binary);
+        assert_eq!(files[2].status, "modified");
+        assert_eq!(files[2].old_path.as_deref(), Some("mode.sh"));
+        assert_eq!(files[2].new_path.as_deref(), Some("mode.sh"));
+        assert_eq!(files[2].old_mode.as_deref(), Some("100644"));
+        assert_eq!(files[2].new_mode.as_deref(), Some("100755"));
+        assert!(files[2].hunks.is_empty());
+
+        assert_eq!(files[3].status, "renamed");
+        assert_eq!(files[3].old_path.as_deref(), Some("old.txt"));
+        assert_eq!(files[3].new_path.as_deref(), Some("new.txt"));
+        assert_eq!(files[3].similarity, Some(100));
+        assert!(files[3].hunks.is_empty());
+    }
+
+    #[test]
+    fn unquotes_paths_with_special_characters() {
+        let files = parse_unified_diff(
+            "diff --git \"a/sp ace \\\"q\\\".txt\" \"b/sp ace \\\"q\\\".txt\"\n\
+             index bca70f3..73c52c3 100644\n\
+             --- \"a/sp ace \\\"q\\\".txt\"\t\n\
+             +++ \"b/sp ace \\\"q\\\".txt\"\t\n\
+             @@ -1 +1 @@\n\
+             -q\n\
+             +Q\n\
+             diff --git \"a/\\303\\274.txt\" \"b/\\303\\274.txt\"\n\
+             new file mode 100644\n\
+             index 0000000..be761e0\n\
+             --- /dev/null\n\
+             +++ \"b/\\303\\274.txt\"\n\
+             @@ -0,0 +1 @@\n\
+             +\u{fc}\n\
+             diff --git \"a/q\\\" b.sh\" \"b/q\\\" b.sh\"\n\
+             old mode 100644\n\
+             new mode 100755\n",
+        );
+
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0].old_path.as_deref(), Some("sp ace \"q\".txt"));
+        assert_eq!(files[0].new_path.as_deref(), Some("sp ace \"q\".txt"));
+        assert_eq!(files[0].additions, 1);
+        assert_eq!(files[0].deletions, 1);
+
+        assert_eq!(files[1].old_path, None);
+        assert_eq!(files[1].new_path.as_deref(), Some("\u{fc}.txt"));
+        assert_eq!(files[1].hunks[0].lines[0].content, "\u{fc}");
+
+        // Without `---`/`+++` lines the paths come from the `diff --git` line.
+        assert_eq!(files[2].old_path.as_deref(), Some("q\" b.sh"));
+        assert_eq!(files[2].new_path.as_deref(), Some("q\" b.sh"));
+        assert_eq!(files[2].new_mode.as_deref(), Some("100755"));
+    }
+
+    /// A repository whose `a.txt` reads `one two three` at HEAD, has `two`
+    /// changed to `TWO` in the index and a line inserted on top in the
+    /// working tree.
+    fn repo_with_staged_and_unstaged_changes() -> TempDir {
+        let repo = init_repo();
+        commit_file(repo.path(), "a.txt", "one\ntwo\nthree\n", "base");
+        write_file(repo.path(), "a.txt", "one\nTWO\nthree\n");
+        git(repo.path(), &["add", "a.txt"]);
+        write_file(repo.path(), "a.txt", "zero\none\nTWO\nthree\n");
+        repo
+    }
+
+    async fn revision_lines(repo: &TempDir, source: DiffSide, target: DiffSide) -> Vec<String> {
+        let files = get_git_revision_diff(path_str(repo), source, target, None, None, None)
+            .await
+            .unwrap_or_else(|e| panic!("diff failed: {}", e));
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].hunks.len(), 1);
+        lines(&files[0].hunks[0])
+            .into_iter()
+            .map(|(marker, content, old, new, _)| {
+                let number = |n: Option<u32>| n.map_or("-".to_string(), |n| n.to_string());
+                format!("{}{} {} {}", marker, content, number(old), number(new))
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn diffs_the_index_against_the_worktree_both_ways() {
+        let repo = repo_with_staged_and_unstaged_changes();
+
+        assert_eq!(
+            revision_lines(&repo, DiffSide::Index, DiffSide::Worktree).await,
+            ["+zero - 1", " one 1 2", " TWO 2 3", " three 3 4"]
+        );
+        assert_eq!(
+            revision_lines(&repo, DiffSide::Worktree, DiffSide::Index).await,
+            ["-zero 1 -", " one 2 1", " TWO 3 2", " three 4 3"]
+        );
+    }
+
+    #[tokio::test]
+    async fn diffs_a_commit_against_the_worktree_both_ways() {
+        let repo = repo_with_staged_and_unstaged_changes();
+        let head = || DiffSide::Commit("HEAD".to_string());
+
+        assert_eq!(
+            revision_lines(&repo, head(), DiffSide::Worktree).await,
+            [
+                "+zero - 1",
+                " one 1 2",
+                "-two 2 -",
+                "+TWO - 3",
+                " three 3 4"
+            ]
+        );
+        assert_eq!(
+            revision_lines(&repo, DiffSide::Worktree, head()).await,
+            [
+                "-zero 1 -",
+                " one 2 1",
+                "-TWO 3 -",
+                "+two - 2",
+                " three 4 3"
+            ]
+        );
+        assert_eq!(
+            revision_lines(&repo, head(), DiffSide::Index).await,
+            [" one 1 1", "-two 2 -", "+TWO - 2", " three 3 3"]
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_identical_sides_and_option_revisions() {
+        let repo = repo_with_staged_and_unstaged_changes();
+
+        let same = get_git_revision_diff(
+            path_str(&repo),
+            DiffSide::Index,
+            DiffSide::Index,
+            None,
+            None,
+            None,
+        )
+        .await;
+        assert!(matches!(same, Err(e) if e == "Source and target must differ"));
+
+        let option = get_git_revision_diff(
+            path_str(&repo),
+            DiffSide::Commit("--output=out.txt".to_string()),
+            DiffSide::Worktree,
+            None,
+            None,
+            None,
+        )
+        .await;
+        assert!(option.is_err());
+        assert!(!repo.path().join("out.txt").exists());
+    }
+}
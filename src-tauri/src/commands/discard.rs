@@ -0,0 +1,476 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::process::Command;
+use walkdir::WalkDir;
+
+use super::diff::unquote_path;
+use super::journal::{capture_state, record_operation};
+use super::patch::{apply_patch, file_diff, select_patch, PatchSelection};
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct DiscardRecord {
+    pub id: String,
+    pub timestamp: i64,
+    pub kind: String,
+    pub files: Vec<String>,
+}
+
+fn trash_dir(repo: &Path) -> PathBuf {
+    repo.join(".git").join("git-client").join("trash")
+}
+
+fn relative_to_repo(repo: &Path, file_path: &str) -> String {
+    Path::new(file_path)
+        .strip_prefix(repo)
+        .ok()
+        .and_then(|p| p.to_str())
+        .unwrap_or(file_path)
+        .replace('\\', "/")
+}
+
+/// Copies the current worktree content of `paths` into a new trash entry so
+/// a discard can be undone with `restore_discarded_changes`.
+fn save_to_trash(repo: &Path, kind: &str, paths: &[String]) -> Result<DiscardRecord, String> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| e.to_string())?;
+    let id = format!("{}-{:09}", now.as_secs(), now.subsec_nanos());
+    let entry_dir = trash_dir(repo).join(&id);
+    let files_dir = entry_dir.join("files");
+
+    let mut files = Vec::new();
+
+    for path in paths {
+        let source = repo.join(path);
+        if !source.exists() {
+            continue;
+        }
+
+        for entry in WalkDir::new(&source)
+            .follow_links(false)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+
+            let Ok(relative) = entry.path().strip_prefix(repo) else {
+                continue;
+            };
+
+            let destination = files_dir.join(relative);
+            if let Some(parent) = destination.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create trash directory: {}", e))?;
+            }
+
+            std::fs::copy(entry.path(), &destination)
+                .map_err(|e| format!("Failed to back up {}: {}", relative.display(), e))?;
+
+            files.push(relative.to_string_lossy().replace('\\', "/"));
+        }
+    }
+
+    let record = DiscardRecord {
+        id,
+        timestamp: now.as_secs() as i64,
+        kind: kind.to_string(),
+        files,
+    };
+
+    std::fs::create_dir_all(&entry_dir)
+        .map_err(|e| format!("Failed to create trash directory: {}", e))?;
+    let manifest = serde_json::to_string_pretty(&record)
+        .map_err(|e| format!("Failed to serialize discard record: {}", e))?;
+    std::fs::write(entry_dir.join("manifest.json"), manifest)
+        .map_err(|e| format!("Failed to write discard record: {}", e))?;
+
+    Ok(record)
+}
+
+/// Drops a trash entry whose discard did not happen.
+fn remove_from_trash(repo: &Path, record: &DiscardRecord) {
+    let _ = std::fs::remove_dir_all(trash_dir(repo).join(&record.id));
+}
+
+/// Paths are matched literally so a file named `*` or `:(glob)x` only
+/// restores itself.
+async fn restore_worktree(repo: &Path, paths: &[String]) -> Result<(), String> {
+    let output = Command::new("git")
+        .arg("--literal-pathspecs")
+        .arg("restore")
+        .arg("--worktree")
+        .arg("--")
+        .args(paths)
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git restore: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git restore failed: {}", stderr));
+    }
+
+    Ok(())
+}
+
+async fn clean_paths(repo: &Path, paths: &[String]) -> Result<(), String> {
+    let output = Command::new("git")
+        .arg("--literal-pathspecs")
+        .arg("clean")
+        .arg("-f")
+        .arg("-d")
+        .arg("--")
+        .args(paths)
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git clean: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git clean failed: {}", stderr));
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn discard_file_changes(
+    repo_path: String,
+    file_paths: Vec<String>,
+) -> Result<DiscardRecord, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let paths: Vec<String> = file_paths
+        .iter()
+        .map(|p| relative_to_repo(repo, p))
+        .collect();
+
+    if paths.is_empty() {
+        return Err("No files selected to discard".to_string());
+    }
+
+    let record = save_to_trash(repo, "files", &paths)?;
+    let before = capture_state(repo).await;
+
+    if let Err(e) = restore_worktree(repo, &paths).await {
+        remove_from_trash(repo, &record);
+        return Err(e);
+    }
+
+    record_operation(repo, "discard changes", true, before).await;
+
+    Ok(record)
+}
+
+#[tauri::command]
+pub async fn discard_hunks(
+    repo_path: String,
+    file_path: String,
+    selection: PatchSelection,
+    context_lines: Option<u32>,
+) -> Result<DiscardRecord, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let relative_path = relative_to_repo(repo, &file_path);
+
+    let diff = file_diff(repo, &relative_path, false, context_lines).await?;
+    if diff.contains("\nBinary files ") || diff.contains("\nGIT binary patch") {
+        return Err("Cannot discard part of a binary file".to_string());
+    }
+
+    let patch = select_patch(&diff, &selection, true)
+        .ok_or_else(|| "No changes selected to discard".to_string())?;
+
+    let record = save_to_trash(repo, "hunks", std::slice::from_ref(&relative_path))?;
+
+    let mut args = vec!["--reverse"];
+    if context_lines == Some(0) {
+        args.push("--unidiff-zero");
+    }
+
+    let before = capture_state(repo).await;
+    if let Err(e) = apply_patch(repo, &patch, &args).await {
+        remove_from_trash(repo, &record);
+        return Err(e);
+    }
+    record_operation(repo, "discard hunks", true, before).await;
+
+    Ok(record)
+}
+
+#[tauri::command]
+pub async fn preview_clean(
+    repo_path: String,
+    file_paths: Option<Vec<String>>,
+) -> Result<Vec<String>, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    // Paths are passed back to `git clean -f`, so they must come out
+    // unquoted and be matched literally rather than as globs.
+    let mut cmd = Command::new("git");
+    cmd.arg("-c")
+        .arg("core.quotePath=false")
+        .arg("--literal-pathspecs")
+        .arg("clean")
+        .arg("-n")
+        .arg("-d");
+
+    if let Some(paths) = &file_paths {
+        if paths.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        cmd.arg("--")
+            .args(paths.iter().map(|p| relative_to_repo(repo, p)));
+    }
+
+    let output = cmd
+        .env("LC_ALL", "C")
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git clean: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git clean failed: {}", stderr));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout
+        .lines()
+        .filter_map(|line| line.strip_prefix("Would remove "))
+        .map(unquote_path)
+        .collect())
+}
+
+#[tauri::command]
+pub async fn discard_untracked(
+    repo_path: String,
+    file_paths: Vec<String>,
+) -> Result<DiscardRecord, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    // Without a pathspec git clean would select every untracked file.
+    if file_paths.is_empty() {
+        return Err("No files selected to discard".to_string());
+    }
+
+    // Only back up and remove what git clean itself would remove.
+    let paths = preview_clean(repo_path.clone(), Some(file_paths)).await?;
+    if paths.is_empty() {
+        return Err("No untracked files to discard".to_string());
+    }
+
+    let record = save_to_trash(repo, "untracked", &paths)?;
+    let before = capture_state(repo).await;
+
+    if let Err(e) = clean_paths(repo, &paths).await {
+        remove_from_trash(repo, &record);
+        return Err(e);
+    }
+
+    record_operation(repo, "discard untracked", true, before).await;
+
+    Ok(record)
+}
+
+#[tauri::command]
+pub fn list_discarded_changes(repo_path: String) -> Result<Vec<DiscardRecord>, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let trash = trash_dir(repo);
+    if !trash.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries =
+        std::fs::read_dir(&trash).map_err(|e| format!("Failed to read trash directory: {}", e))?;
+
+    let mut records = Vec::new();
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        let manifest = entry.path().join("manifest.json");
+        let Ok(content) = std::fs::read_to_string(&manifest) else {
+            continue;
+        };
+
+        if let Ok(record) = serde_json::from_str::<DiscardRecord>(&content) {
+            records.push(record);
+        }
+    }
+
+    records.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(records)
+}
+
+#[tauri::command]
+pub async fn restore_discarded_changes(
+    repo_path: String,
+    id: String,
+) -> Result<Vec<String>, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    if id.is_empty() || id.contains(['/', '\\']) || id.contains("..") {
+        return Err("Invalid discard record id".to_string());
+    }
+
+    let entry_dir = trash_dir(repo).join(&id);
+    let content = std::fs::read_to_string(entry_dir.join("manifest.json"))
+        .map_err(|_| format!("Discard record '{}' not found", id))?;
+    let record: DiscardRecord = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse discard record: {}", e))?;
+
+    let before = capture_state(repo).await;
+
+    for file in &record.files {
+        let source = entry_dir.join("files").join(file);
+        let destination = repo.join(file);
+
+        if let Some(parent) = destination.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory: {}", e))?;
+        }
+
+        std::fs::copy(&source, &destination)
+            .map_err(|e| format!("Failed to restore {}: {}", file, e))?;
+    }
+
+    record_operation(repo, "restore discarded", true, before).await;
+
+    Ok(record.files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_util::{commit_file, init_repo, path_str, write_file};
+
+    #[tokio::test]
+    async fn refuses_to_discard_without_paths() {
+        let repo = init_repo();
+        commit_file(repo.path(), "tracked.txt", "x\n", "first");
+        write_file(repo.path(), "keep.txt", "keep\n");
+
+        let result = discard_untracked(path_str(&repo), Vec::new()).await;
+        assert!(result.is_err());
+        assert!(repo.path().join("keep.txt").exists());
+
+        let preview = preview_clean(path_str(&repo), Some(Vec::new())).await;
+        assert!(preview.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn discards_only_the_literal_file() {
+        let repo = init_repo();
+        commit_file(repo.path(), "*", "star\n", "first");
+        commit_file(repo.path(), "a.txt", "a\n", "second");
+        write_file(repo.path(), "*", "changed star\n");
+        write_file(repo.path(), "a.txt", "changed a\n");
+
+        let record = discard_file_changes(path_str(&repo), vec!["*".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(record.files, ["*"]);
+        assert_eq!(
+            std::fs::read_to_string(repo.path().join("*")).unwrap(),
+            "star\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(repo.path().join("a.txt")).unwrap(),
+            "changed a\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_discard_leaves_no_trash_entry() {
+        let repo = init_repo();
+        commit_file(repo.path(), "a.txt", "a\n", "first");
+
+        let result = discard_file_changes(path_str(&repo), vec!["missing.txt".to_string()]).await;
+        assert!(result.is_err());
+        assert!(list_discarded_changes(path_str(&repo)).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn discards_special_paths_literally() {
+        let repo = init_repo();
+        commit_file(repo.path(), "tracked.txt", "x\n", "first");
+        write_file(repo.path(), "ünï cödé.txt", "u\n");
+        write_file(repo.path(), "a*.txt", "star\n");
+        write_file(repo.path(), "ab.txt", "keep\n");
+
+        let preview = preview_clean(path_str(&repo), None).await.unwrap();
+        assert!(preview.contains(&"ünï cödé.txt".to_string()));
+        assert!(preview.contains(&"a*.txt".to_string()));
+
+        let record = discard_untracked(
+            path_str(&repo),
+            vec!["ünï cödé.txt".to_string(), "a*.txt".to_string()],
+        )
+        .await
+        .unwrap();
+
+        let mut files = record.files.clone();
+        files.sort();
+        assert_eq!(files, ["a*.txt", "ünï cödé.txt"]);
+        assert!(!repo.path().join("ünï cödé.txt").exists());
+        assert!(!repo.path().join("a*.txt").exists());
+        assert!(repo.path().join("ab.txt").exists());
+
+        let restored = restore_discarded_changes(path_str(&repo), record.id)
+            .await
+            .unwrap();
+        assert_eq!(restored.len(), 2);
+        assert!(repo.path().join("ünï cödé.txt").exists());
+    }
+}
@@ -1,8 +1,32 @@
-use std::collections::HashMap;
 use std::path::Path;
+use std::process::Stdio;
+use tauri::{AppHandle, Emitter};
+use tokio::io::AsyncReadExt;
 use tokio::process::Command;
 use walkdir::WalkDir;
 
+use super::diff::{parse_unified_diff, FileDiff, PATCH_FORMAT_ARGS};
+use super::journal::{capture_state, finish_pending, record_operation};
+use super::patch::unified_arg;
+
+/// Runs git with a C locale and returns its trimmed stdout.
+pub(crate) async fn run_git(repo: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .env("LC_ALL", "C")
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git {}: {}", args[0], e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git {} failed: {}", args[0], stderr.trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
 #[tauri::command]
 pub async fn get_git_version() -> Result<String, String> {
     let output = Command::new("git")
@@ -69,8 +93,405 @@ pub fn is_git_repo(path: String) -> Result<bool, String> {
     Ok(git_dir.exists() && git_dir.is_dir())
 }
 
+#[derive(Clone, serde::Serialize)]
+pub struct CloneProgress {
+    pub target_path: String,
+    pub phase: String,
+    pub percent: Option<u32>,
+    pub current: Option<u64>,
+    pub total: Option<u64>,
+    pub message: String,
+}
+
+/// Derives the directory name `git clone` would pick for `url`.
+fn clone_directory_name(url: &str) -> Option<String> {
+    let trimmed = url.trim().trim_end_matches(['/', '\\']);
+    let trimmed = trimmed
+        .strip_suffix("/.git")
+        .or_else(|| trimmed.strip_suffix("\\.git"))
+        .unwrap_or(trimmed);
+    let trimmed = trimmed.strip_suffix(".git").unwrap_or(trimmed);
+
+    let name = trimmed.rsplit(['/', '\\', ':']).next().unwrap_or("").trim();
+
+    if name.is_empty() {
+        None
+    } else {
+        Some(name.to_string())
+    }
+}
+
+/// Parses a `git clone --progress` line such as
+/// `Receiving objects:  45% (450/1000), 1.20 MiB | 512.00 KiB/s`.
+fn parse_clone_progress(target_path: &str, line: &str) -> CloneProgress {
+    let message = line.trim().to_string();
+    let body = message.strip_prefix("remote: ").unwrap_or(&message);
+
+    let (phase, rest) = match body.split_once(':') {
+        Some((phase, rest)) => (phase.trim().to_string(), rest),
+        None => (body.to_string(), ""),
+    };
+
+    let percent = rest
+        .split_once('%')
+        .and_then(|(value, _)| value.trim().parse::<u32>().ok());
+
+    let (current, total) = rest
+        .split_once('(')
+        .and_then(|(_, counts)| counts.split_once(')'))
+        .and_then(|(counts, _)| counts.split_once('/'))
+        .map(|(current, total)| {
+            (
+                current.trim().parse::<u64>().ok(),
+                total.trim().parse::<u64>().ok(),
+            )
+        })
+        .unwrap_or((None, None));
+
+    CloneProgress {
+        target_path: target_path.to_string(),
+        phase,
+        percent,
+        current,
+        total,
+        message,
+    }
+}
+
+#[tauri::command]
+pub async fn clone_git_repo(
+    app: AppHandle,
+    url: String,
+    target_dir: String,
+    branch: Option<String>,
+    depth: Option<u32>,
+) -> Result<String, String> {
+    clone_repo(url, target_dir, branch, depth, |progress| {
+        let _ = app.emit("git-clone-progress", progress);
+    })
+    .await
+}
+
+/// Clones `url` into a new directory under `target_dir`, reporting each
+/// progress line to `on_progress`. Returns the path of the new clone.
+async fn clone_repo(
+    url: String,
+    target_dir: String,
+    branch: Option<String>,
+    depth: Option<u32>,
+    mut on_progress: impl FnMut(CloneProgress),
+) -> Result<String, String> {
+    let url = url.trim().to_string();
+    if url.is_empty() {
+        return Err("Repository URL cannot be empty".to_string());
+    }
+
+    let base_path = Path::new(&target_dir);
+    if base_path.exists() && !base_path.is_dir() {
+        return Err("Target path is not a directory".to_string());
+    }
+
+    let directory_name = clone_directory_name(&url)
+        .ok_or_else(|| "Could not derive a directory name from the repository URL".to_string())?;
+    let destination = base_path.join(&directory_name);
+
+    if destination.exists()
+        && std::fs::read_dir(&destination)
+            .map(|mut entries| entries.next().is_some())
+            .unwrap_or(true)
+    {
+        return Err(format!(
+            "Destination '{}' already exists and is not empty",
+            destination.display()
+        ));
+    }
+
+    std::fs::create_dir_all(base_path)
+        .map_err(|e| format!("Failed to create target directory: {}", e))?;
+
+    let destination_str = destination.to_string_lossy().to_string();
+
+    let mut cmd = Command::new("git");
+    cmd.arg("clone").arg("--progress");
+
+    if let Some(branch_name) = branch.as_deref().filter(|b| !b.trim().is_empty()) {
+        cmd.arg("--branch").arg(branch_name.trim());
+    }
+
+    if let Some(depth_val) = depth {
+        // A plain local path uses hardlinks and silently ignores --depth.
+        if Path::new(&url).exists() {
+            cmd.arg("--no-local");
+        }
+        cmd.arg("--depth").arg(depth_val.to_string());
+    }
+
+    let mut child = cmd
+        .arg("--")
+        .arg(&url)
+        .arg(&destination_str)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("LC_ALL", "C")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to execute git clone: {}", e))?;
+
+    let mut stderr = child
+        .stderr
+        .take()
+        .ok_or_else(|| "Failed to capture git clone output".to_string())?;
+
+    // Progress lines are terminated by '\r' while git rewrites them in place.
+    let mut buffer = [0u8; 4096];
+    let mut pending: Vec<u8> = Vec::new();
+    let mut messages: Vec<String> = Vec::new();
+
+    loop {
+        let read = stderr
+            .read(&mut buffer)
+            .await
+            .map_err(|e| format!("Failed to read git clone output: {}", e))?;
+
+        if read == 0 {
+            break;
+        }
+
+        for &byte in &buffer[..read] {
+            if byte != b'\r' && byte != b'\n' {
+                pending.push(byte);
+                continue;
+            }
+
+            let line = String::from_utf8_lossy(&pending).trim().to_string();
+            pending.clear();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            on_progress(parse_clone_progress(&destination_str, &line));
+
+            if byte == b'\n' {
+                messages.push(line);
+            }
+        }
+    }
+
+    if !pending.is_empty() {
+        messages.push(String::from_utf8_lossy(&pending).trim().to_string());
+    }
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| format!("Failed to wait for git clone: {}", e))?;
+
+    if !status.success() {
+        return Err(format!("Git clone failed: {}", messages.join("\n")));
+    }
+
+    Ok(destination_str)
+}
+
+#[derive(Default, serde::Serialize)]
+pub struct BranchHeader {
+    pub oid: Option<String>,
+    pub head: Option<String>,
+    pub upstream: Option<String>,
+    pub ahead: u32,
+    pub behind: u32,
+}
+
+#[derive(serde::Serialize)]
+pub struct SubmoduleState {
+    pub commit_changed: bool,
+    pub tracked_changes: bool,
+    pub untracked_changes: bool,
+}
+
+#[derive(serde::Serialize)]
+pub struct StatusEntry {
+    pub path: String,
+    pub original_path: Option<String>,
+    pub kind: String,
+    pub index_status: String,
+    pub worktree_status: String,
+    pub conflict: Option<String>,
+    pub similarity: Option<u32>,
+    pub submodule: Option<SubmoduleState>,
+}
+
+#[derive(Default, serde::Serialize)]
+pub struct RepoStatus {
+    pub branch: BranchHeader,
+    pub entries: Vec<StatusEntry>,
+}
+
+fn status_code_name(code: char) -> &'static str {
+    match code {
+        '.' => "unmodified",
+        'M' => "modified",
+        'T' => "type-changed",
+        'A' => "added",
+        'D' => "deleted",
+        'R' => "renamed",
+        'C' => "copied",
+        'U' => "unmerged",
+        '?' => "untracked",
+        '!' => "ignored",
+        _ => "unknown",
+    }
+}
+
+/// Names the conflict type of an unmerged `XY` code.
+pub(crate) fn conflict_type_name(xy: &str) -> &'static str {
+    match xy {
+        "DD" => "both-deleted",
+        "AU" => "added-by-us",
+        "UD" => "deleted-by-them",
+        "UA" => "added-by-them",
+        "DU" => "deleted-by-us",
+        "AA" => "both-added",
+        "UU" => "both-modified",
+        _ => "unknown",
+    }
+}
+
+fn parse_submodule_state(field: &str) -> Option<SubmoduleState> {
+    let mut chars = field.chars();
+    if chars.next() != Some('S') {
+        return None;
+    }
+
+    Some(SubmoduleState {
+        commit_changed: chars.next() == Some('C'),
+        tracked_changes: chars.next() == Some('M'),
+        untracked_changes: chars.next() == Some('U'),
+    })
+}
+
+fn status_entry(
+    kind: &str,
+    xy: &str,
+    submodule: &str,
+    path: &str,
+    original_path: Option<&str>,
+    similarity: Option<u32>,
+) -> StatusEntry {
+    let mut codes = xy.chars();
+    let index_code = codes.next().unwrap_or('.');
+    let worktree_code = codes.next().unwrap_or('.');
+
+    StatusEntry {
+        path: path.replace('\\', "/"),
+        original_path: original_path.map(|p| p.replace('\\', "/")),
+        kind: kind.to_string(),
+        index_status: status_code_name(index_code).to_string(),
+        worktree_status: status_code_name(worktree_code).to_string(),
+        conflict: (kind == "unmerged").then(|| conflict_type_name(xy).to_string()),
+        similarity,
+        submodule: parse_submodule_state(submodule),
+    }
+}
+
+/// Parses `git status --porcelain=v2 -z --branch` output.
+pub(crate) fn parse_status_v2(stdout: &str) -> RepoStatus {
+    let mut status = RepoStatus::default();
+    let mut records = stdout.split('\0');
+
+    while let Some(record) = records.next() {
+        if let Some(header) = record.strip_prefix("# ") {
+            let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+            match key {
+                "branch.oid" if value != "(initial)" => {
+                    status.branch.oid = Some(value.to_string());
+                }
+                "branch.head" if value != "(detached)" => {
+                    status.branch.head = Some(value.to_string());
+                }
+                "branch.upstream" => {
+                    status.branch.upstream = Some(value.to_string());
+                }
+                "branch.ab" => {
+                    for count in value.split_whitespace() {
+                        if let Some(ahead) = count.strip_prefix('+') {
+                            status.branch.ahead = ahead.parse().unwrap_or(0);
+                        } else if let Some(behind) = count.strip_prefix('-') {
+                            status.branch.behind = behind.parse().unwrap_or(0);
+                        }
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        let Some((record_type, rest)) = record.split_once(' ') else {
+            continue;
+        };
+
+        match record_type {
+            // 1 <XY> <sub> <mH> <mI> <mW> <hH> <hI> <path>
+            "1" => {
+                let fields: Vec<&str> = rest.splitn(8, ' ').collect();
+                if fields.len() == 8 {
+                    status.entries.push(status_entry(
+                        "changed", fields[0], fields[1], fields[7], None, None,
+                    ));
+                }
+            }
+            // 2 <XY> <sub> <mH> <mI> <mW> <hH> <hI> <X><score> <path>\0<origPath>
+            "2" => {
+                let fields: Vec<&str> = rest.splitn(9, ' ').collect();
+                let original_path = records.next();
+                if fields.len() == 9 {
+                    let score = fields[7];
+                    let kind = if score.starts_with('C') {
+                        "copied"
+                    } else {
+                        "renamed"
+                    };
+                    let similarity = score.get(1..).and_then(|s| s.parse().ok());
+                    status.entries.push(status_entry(
+                        kind,
+                        fields[0],
+                        fields[1],
+                        fields[8],
+                        original_path,
+                        similarity,
+                    ));
+                }
+            }
+            // u <XY> <sub> <m1> <m2> <m3> <mW> <h1> <h2> <h3> <path>
+            "u" => {
+                let fields: Vec<&str> = rest.splitn(10, ' ').collect();
+                if fields.len() == 10 {
+                    status.entries.push(status_entry(
+                        "unmerged", fields[0], fields[1], fields[9], None, None,
+                    ));
+                }
+            }
+            "?" => {
+                status
+                    .entries
+                    .push(status_entry("untracked", "??", "N...", rest, None, None));
+            }
+            "!" => {
+                status
+                    .entries
+                    .push(status_entry("ignored", "!!", "N...", rest, None, None));
+            }
+            _ => {}
+        }
+    }
+
+    status
+}
+
 #[tauri::command]
-pub async fn get_git_status(repo_path: String) -> Result<HashMap<String, String>, String> {
+pub async fn get_git_status(repo_path: String) -> Result<RepoStatus, String> {
     let repo = Path::new(&repo_path);
 
     if !repo.exists() {
@@ -79,12 +500,14 @@ pub async fn get_git_status(repo_path: String) -> Result<HashMap<String, String>
 
     let git_dir = repo.join(".git");
     if !git_dir.exists() {
-        return Ok(HashMap::new());
+        return Ok(RepoStatus::default());
     }
 
     let output = Command::new("git")
         .arg("status")
-        .arg("--porcelain")
+        .arg("--porcelain=v2")
+        .arg("-z")
+        .arg("--branch")
         .arg("--untracked-files=all")
         .current_dir(repo)
         .output()
@@ -96,49 +519,7 @@ pub async fn get_git_status(repo_path: String) -> Result<HashMap<String, String>
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut status_map = HashMap::new();
-
-    for line in stdout.lines() {
-        if line.len() < 4 {
-            continue;
-        }
-
-        let status_code = &line[0..2];
-        let file_path = line[3..].trim();
-
-        if file_path.is_empty() {
-            continue;
-        }
-
-        let staged_char = status_code.chars().nth(0).unwrap_or(' ');
-        let working_char = status_code.chars().nth(1).unwrap_or(' ');
-
-        let normalized_status = match (staged_char, working_char) {
-            (' ', 'M') => "modified",
-            ('M', ' ') => "modified-staged",
-            ('M', 'M') => "modified-staged",
-            ('A', ' ') => "added",
-            ('A', 'M') => "added-modified",
-            ('A', 'D') => "added-deleted",
-            ('D', ' ') => "deleted",
-            ('R', ' ') => "renamed",
-            ('C', ' ') => "copied",
-            ('U', _) => "unmerged",
-            ('?', '?') => "untracked",
-            _ => "unknown",
-        };
-
-        let full_path = if file_path.contains(" -> ") {
-            file_path.split(" -> ").last().unwrap_or(file_path)
-        } else {
-            file_path
-        };
-
-        let normalized_path = full_path.replace('\\', "/");
-        status_map.insert(normalized_path, normalized_status.to_string());
-    }
-
-    Ok(status_map)
+    Ok(parse_status_v2(&stdout))
 }
 
 #[derive(serde::Serialize)]
@@ -267,8 +648,104 @@ pub async fn get_git_blame(repo_path: String, file_path: String) -> Result<Vec<B
     Ok(blame_lines)
 }
 
+#[derive(serde::Serialize)]
+pub struct BranchInfo {
+    pub ref_name: String,
+    pub short_name: String,
+    pub kind: String,
+    pub remote: Option<String>,
+    pub upstream: Option<String>,
+    pub upstream_gone: bool,
+    pub ahead: u32,
+    pub behind: u32,
+    pub hash: String,
+    pub subject: String,
+    pub author_date: String,
+    pub is_current: bool,
+}
+
+/// Parses `%(upstream:track,nobracket)` values such as `ahead 1, behind 2`.
+fn parse_upstream_track(track: &str) -> (u32, u32, bool) {
+    let mut ahead = 0;
+    let mut behind = 0;
+
+    for part in track.split(',') {
+        let part = part.trim();
+        if let Some(count) = part.strip_prefix("ahead ") {
+            ahead = count.parse().unwrap_or(0);
+        } else if let Some(count) = part.strip_prefix("behind ") {
+            behind = count.parse().unwrap_or(0);
+        }
+    }
+
+    (ahead, behind, track.trim() == "gone")
+}
+
+/// Parses the `for-each-ref` records read by `get_git_branches`. Names are
+/// taken from the full ref rather than `%(refname:short)`, which turns into
+/// `heads/x` or `remotes/origin/x` when a local and remote name collide.
+fn parse_branch_refs(stdout: &str, remotes: &[String]) -> Vec<BranchInfo> {
+    let mut branches = Vec::new();
+
+    for record in stdout.split('\u{001F}') {
+        let parts: Vec<&str> = record.trim_start_matches('\n').split('\u{001E}').collect();
+        if parts.len() < 8 {
+            continue;
+        }
+
+        // Skip symbolic refs such as refs/remotes/origin/HEAD.
+        if !parts[7].is_empty() {
+            continue;
+        }
+
+        let ref_name = parts[0].to_string();
+        let (kind, short_name, remote) = if let Some(rest) = ref_name.strip_prefix("refs/remotes/")
+        {
+            // Refs under a removed remote fall back to their first component;
+            // a ref directly under refs/remotes/ has no remote at all.
+            let remote = remotes
+                .iter()
+                .filter(|r| rest.starts_with(&format!("{}/", r)))
+                .max_by_key(|r| r.len())
+                .cloned()
+                .or_else(|| rest.split_once('/').map(|(r, _)| r.to_string()));
+            ("remote", rest, remote)
+        } else if let Some(rest) = ref_name.strip_prefix("refs/heads/") {
+            ("local", rest, None)
+        } else {
+            continue;
+        };
+
+        let upstream = parts[1]
+            .strip_prefix("refs/remotes/")
+            .or_else(|| parts[1].strip_prefix("refs/heads/"))
+            .unwrap_or(parts[1]);
+        let (ahead, behind, upstream_gone) = parse_upstream_track(parts[2]);
+
+        branches.push(BranchInfo {
+            short_name: short_name.to_string(),
+            kind: kind.to_string(),
+            remote,
+            upstream: Some(upstream.to_string()).filter(|u| !u.is_empty()),
+            upstream_gone,
+            ahead,
+            behind,
+            hash: parts[3].to_string(),
+            subject: parts[4].to_string(),
+            author_date: parts[5].to_string(),
+            is_current: parts[6] == "*",
+            ref_name,
+        });
+    }
+
+    branches.sort_by(|a, b| {
+        (a.kind != "local", &a.short_name).cmp(&(b.kind != "local", &b.short_name))
+    });
+    branches
+}
+
 #[tauri::command]
-pub async fn get_git_branches(repo_path: String) -> Result<Vec<String>, String> {
+pub async fn get_git_branches(repo_path: String) -> Result<Vec<BranchInfo>, String> {
     let repo = Path::new(&repo_path);
 
     if !repo.exists() {
@@ -280,51 +757,37 @@ pub async fn get_git_branches(repo_path: String) -> Result<Vec<String>, String>
         return Err("Not a git repository".to_string());
     }
 
+    let remotes_output = Command::new("git")
+        .arg("remote")
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git remote: {}", e))?;
+
+    let remotes: Vec<String> = String::from_utf8_lossy(&remotes_output.stdout)
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect();
+
     let output = Command::new("git")
-        .arg("branch")
-        .arg("-a")
+        .arg("for-each-ref")
+        .arg("--format=%(refname)%1E%(upstream)%1E%(upstream:track,nobracket)%1E%(objectname)%1E%(subject)%1E%(authordate:iso)%1E%(HEAD)%1E%(symref)%1F")
+        .arg("refs/heads")
+        .arg("refs/remotes")
+        .env("LC_ALL", "C")
         .current_dir(repo)
         .output()
         .await
-        .map_err(|e| format!("Failed to execute git branch: {}", e))?;
+        .map_err(|e| format!("Failed to execute git for-each-ref: {}", e))?;
 
     if !output.status.success() {
-        return Err("Git branch command failed".to_string());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git for-each-ref command failed: {}", stderr));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut branches = Vec::new();
-
-    for line in stdout.lines() {
-        let branch = line.trim();
-        if branch.is_empty() {
-            continue;
-        }
-
-        let branch_name = if branch.starts_with("* ") {
-            branch
-                .strip_prefix("* ")
-                .unwrap_or(branch)
-                .trim()
-                .to_string()
-        } else if branch.starts_with("remotes/") {
-            let parts: Vec<&str> = branch.split('/').collect();
-            if parts.len() >= 3 {
-                parts[2..].join("/")
-            } else {
-                branch.to_string()
-            }
-        } else {
-            branch.trim().to_string()
-        };
-
-        if !branch_name.is_empty() && !branches.contains(&branch_name.to_string()) {
-            branches.push(branch_name.to_string());
-        }
-    }
-
-    branches.sort();
-    Ok(branches)
+    Ok(parse_branch_refs(&stdout, &remotes))
 }
 
 #[tauri::command]
@@ -369,6 +832,8 @@ pub async fn checkout_git_branch(repo_path: String, branch_name: String) -> Resu
         return Err("Not a git repository".to_string());
     }
 
+    let before = capture_state(repo).await;
+
     let output = Command::new("git")
         .arg("checkout")
         .arg(&branch_name)
@@ -382,51 +847,72 @@ pub async fn checkout_git_branch(repo_path: String, branch_name: String) -> Resu
         return Err(format!("Git checkout failed: {}", stderr));
     }
 
+    record_operation(repo, "checkout", true, before).await;
     Ok(())
 }
 
 #[tauri::command]
-pub async fn pull_git_repo(repo_path: String, branch_name: String) -> Result<(), String> {
+pub async fn create_git_branch(
+    repo_path: String,
+    branch_name: String,
+    start_point: Option<String>,
+    checkout: Option<bool>,
+) -> Result<(), String> {
     let repo = Path::new(&repo_path);
+
     if !repo.exists() {
         return Err("Repository path does not exist".to_string());
     }
+
     let git_dir = repo.join(".git");
     if !git_dir.exists() {
         return Err("Not a git repository".to_string());
     }
-    let output = Command::new("git")
-        .arg("pull")
-        .arg("origin")
-        .arg(branch_name)
+
+    let branch_name = branch_name.trim().to_string();
+    if branch_name.is_empty() {
+        return Err("Branch name cannot be empty".to_string());
+    }
+
+    let before = capture_state(repo).await;
+
+    // `git checkout` treats `--end-of-options` as a pathspec, so use switch.
+    let mut cmd = Command::new("git");
+    if checkout.unwrap_or(false) {
+        cmd.arg("switch")
+            .arg("-c")
+            .arg(&branch_name)
+            .arg("--end-of-options");
+    } else {
+        cmd.arg("branch").arg("--end-of-options").arg(&branch_name);
+    }
+
+    if let Some(start) = start_point.as_deref().filter(|s| !s.trim().is_empty()) {
+        cmd.arg(start.trim());
+    }
+
+    let output = cmd
         .current_dir(repo)
         .output()
         .await
-        .map_err(|e| format!("Failed to execute git pull: {}", e))?;
+        .map_err(|e| format!("Failed to execute git branch: {}", e))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Git pull failed: {}", stderr));
+        return Err(format!("Git branch creation failed: {}", stderr));
     }
 
+    record_operation(repo, "create branch", checkout.unwrap_or(false), before).await;
     Ok(())
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
-pub struct Commit {
-    pub hash: String,
-    pub author: String,
-    pub email: String,
-    pub date: String,
-    pub message: String,
-}
-
 #[tauri::command]
-pub async fn get_git_commits(
+pub async fn rename_git_branch(
     repo_path: String,
-    skip: Option<u32>,
-    limit: Option<u32>,
-) -> Result<Vec<Commit>, String> {
+    old_name: String,
+    new_name: String,
+    force: Option<bool>,
+) -> Result<(), String> {
     let repo = Path::new(&repo_path);
 
     if !repo.exists() {
@@ -438,98 +924,1058 @@ pub async fn get_git_commits(
         return Err("Not a git repository".to_string());
     }
 
-    let mut cmd = Command::new("git");
-    cmd.arg("--no-pager")
-        .arg("log")
-        .arg("--pretty=format:%H%x1E%an%x1E%ae%x1E%ai%x1E%s%x1F");
-
-    if let Some(limit_val) = limit {
-        let total_needed = skip.unwrap_or(0) + limit_val;
-        cmd.arg("-n").arg(total_needed.to_string());
+    let new_name = new_name.trim().to_string();
+    if new_name.is_empty() {
+        return Err("Branch name cannot be empty".to_string());
     }
 
-    let output = cmd
+    let before = capture_state(repo).await;
+
+    let output = Command::new("git")
+        .arg("branch")
+        .arg(if force.unwrap_or(false) { "-M" } else { "-m" })
+        .arg("--end-of-options")
+        .arg(old_name.trim())
+        .arg(&new_name)
         .current_dir(repo)
         .output()
         .await
-        .map_err(|e| format!("Failed to execute git log: {}", e))?;
+        .map_err(|e| format!("Failed to execute git branch -m: {}", e))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Git log command failed: {}", stderr));
+        return Err(format!("Git branch rename failed: {}", stderr));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    record_operation(repo, "rename branch", false, before).await;
+    Ok(())
+}
 
-    if stdout.trim().is_empty() {
-        return Ok(Vec::new());
+#[tauri::command]
+pub async fn delete_git_branch(
+    repo_path: String,
+    branch_name: String,
+    force: Option<bool>,
+    delete_remote: Option<bool>,
+) -> Result<(), String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
     }
 
-    let mut commits = Vec::new();
-    let record_separator = '\u{001E}';
-    let unit_separator = '\u{001F}';
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
 
-    let records: Vec<&str> = stdout.split(unit_separator).collect();
-    let skip_count = skip.unwrap_or(0) as usize;
-    let mut skipped = 0;
+    let branch_name = branch_name.trim().to_string();
+    let current_branch = get_current_git_branch(repo_path.clone()).await?;
+    if current_branch == branch_name {
+        return Err(format!(
+            "Cannot delete branch '{}' because it is the checked-out branch",
+            branch_name
+        ));
+    }
 
-    for record in records.iter() {
-        if record.trim().is_empty() {
-            continue;
-        }
+    // The upstream config disappears with the branch, so read it first. Only
+    // the branch's own remote upstream is ever deleted.
+    let mut remote_target = None;
+    if delete_remote.unwrap_or(false) {
+        let upstream = run_git(
+            repo,
+            &[
+                "for-each-ref",
+                "--format=%(upstream:remotename)%00%(upstream:remoteref)",
+                &format!("refs/heads/{}", branch_name),
+            ],
+        )
+        .await?;
+
+        match upstream.split_once('\0') {
+            Some((remote, remote_ref)) if !remote.is_empty() && remote != "." => {
+                remote_target = Some((remote.to_string(), remote_ref.to_string()));
+            }
+            _ => {
+                return Err(format!(
+                    "Branch '{}' has no remote upstream to delete",
+                    branch_name
+                ));
+            }
+        }
+    }
+
+    let before = capture_state(repo).await;
+
+    let output = Command::new("git")
+        .arg("branch")
+        .arg(if force.unwrap_or(false) { "-D" } else { "-d" })
+        .arg("--end-of-options")
+        .arg(&branch_name)
+        .env("LC_ALL", "C")
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git branch -d: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("is not fully merged") {
+            return Err(format!(
+                "Branch '{}' is not fully merged; force delete it to discard its commits",
+                branch_name
+            ));
+        }
+        if stderr.contains("checked out at") || stderr.contains("used by worktree") {
+            return Err(format!(
+                "Cannot delete branch '{}' because it is checked out in another worktree",
+                branch_name
+            ));
+        }
+        return Err(format!("Git branch delete failed: {}", stderr));
+    }
+
+    record_operation(repo, "delete branch", false, before).await;
+
+    if let Some((remote, remote_ref)) = remote_target {
+        let output = Command::new("git")
+            .arg("push")
+            .arg("--delete")
+            .arg("--end-of-options")
+            .arg(&remote)
+            .arg(&remote_ref)
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .current_dir(repo)
+            .output()
+            .await
+            .map_err(|e| format!("Failed to execute git push --delete: {}", e))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!(
+                "Deleted local branch '{}' but deleting '{}' on '{}' failed: {}",
+                branch_name, remote_ref, remote, stderr
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_branch_upstream(
+    repo_path: String,
+    branch_name: String,
+    upstream: Option<String>,
+) -> Result<(), String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let before = capture_state(repo).await;
+
+    let mut cmd = Command::new("git");
+    cmd.arg("branch");
+
+    match upstream.as_deref().map(str::trim).filter(|u| !u.is_empty()) {
+        Some(upstream) => cmd.arg(format!("--set-upstream-to={}", upstream)),
+        None => cmd.arg("--unset-upstream"),
+    };
+
+    let output = cmd
+        .arg("--end-of-options")
+        .arg(branch_name.trim())
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git branch: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git branch upstream update failed: {}", stderr));
+    }
+
+    record_operation(repo, "set upstream", false, before).await;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn pull_git_repo(repo_path: String, branch_name: String) -> Result<(), String> {
+    let repo = Path::new(&repo_path);
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+    let before = capture_state(repo).await;
+
+    let output = Command::new("git")
+        .arg("pull")
+        .arg("origin")
+        .arg(branch_name)
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git pull: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git pull failed: {}", stderr));
+    }
+
+    record_operation(repo, "pull", true, before).await;
+    Ok(())
+}
+
+#[derive(serde::Serialize)]
+pub struct PushRefUpdate {
+    pub status: String,
+    pub local_ref: String,
+    pub remote_ref: String,
+    pub summary: String,
+    pub reason: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+pub struct PushResult {
+    pub remote: String,
+    pub success: bool,
+    pub rejected: bool,
+    pub non_fast_forward: bool,
+    pub upstream_set: bool,
+    pub updates: Vec<PushRefUpdate>,
+    pub message: String,
+}
+
+/// Parses the ref lines of `git push --porcelain`, e.g.
+/// `!\trefs/heads/main:refs/heads/main\t[rejected] (fetch first)`.
+pub(crate) fn parse_push_porcelain(stdout: &str) -> Vec<PushRefUpdate> {
+    let mut updates = Vec::new();
+
+    for line in stdout.lines() {
+        let mut fields = line.splitn(3, '\t');
+        let (Some(flag), Some(refs), Some(summary)) = (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+
+        let status = match flag {
+            " " => "fast-forward",
+            "+" => "forced",
+            "-" => "deleted",
+            "*" => "new",
+            "!" => "rejected",
+            "=" => "up-to-date",
+            _ => continue,
+        }
+        .to_string();
+
+        let (local_ref, remote_ref) = refs.split_once(':').unwrap_or(("", refs));
+
+        let (summary, reason) = match summary.split_once(" (") {
+            Some((summary, reason)) => (
+                summary.to_string(),
+                Some(reason.trim_end_matches(')').to_string()),
+            ),
+            None => (summary.to_string(), None),
+        };
+
+        updates.push(PushRefUpdate {
+            status,
+            local_ref: local_ref.to_string(),
+            remote_ref: remote_ref.to_string(),
+            summary,
+            reason,
+        });
+    }
+
+    updates
+}
+
+#[tauri::command]
+pub async fn push_git_branch(
+    repo_path: String,
+    branch_name: String,
+    remote: Option<String>,
+    force_with_lease: Option<bool>,
+    push_tags: Option<bool>,
+) -> Result<PushResult, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let branch_name = branch_name.trim().to_string();
+    if branch_name.is_empty() {
+        return Err("Branch name cannot be empty".to_string());
+    }
+
+    let upstream_output = Command::new("git")
+        .arg("config")
+        .arg("--get")
+        .arg(format!("branch.{}.remote", branch_name))
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git config: {}", e))?;
+
+    let upstream_remote = String::from_utf8_lossy(&upstream_output.stdout)
+        .trim()
+        .to_string();
+    let has_upstream = upstream_output.status.success() && !upstream_remote.is_empty();
+
+    let remote = match remote
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+    {
+        Some(remote) => remote,
+        None if has_upstream => upstream_remote,
+        None => {
+            return Err(format!(
+                "Branch '{}' has no upstream, choose a remote to push to",
+                branch_name
+            ))
+        }
+    };
+
+    let mut cmd = Command::new("git");
+    cmd.arg("push").arg("--porcelain");
+
+    let upstream_set = !has_upstream;
+    if upstream_set {
+        cmd.arg("--set-upstream");
+    }
+
+    if force_with_lease.unwrap_or(false) {
+        cmd.arg("--force-with-lease");
+    }
+
+    if push_tags.unwrap_or(false) {
+        cmd.arg("--tags");
+    }
+
+    let output = cmd
+        .arg("--end-of-options")
+        .arg(&remote)
+        .arg(format!("refs/heads/{0}:refs/heads/{0}", branch_name))
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("LC_ALL", "C")
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git push: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let updates = parse_push_porcelain(&stdout);
+
+    let rejected = updates.iter().any(|u| u.status == "rejected");
+
+    // Anything other than a ref rejection (auth, network, bad remote) stays an error.
+    if !output.status.success() && !rejected {
+        return Err(format!("Git push failed: {}", stderr));
+    }
+
+    let non_fast_forward = updates.iter().any(|u| {
+        u.status == "rejected"
+            && matches!(
+                u.reason.as_deref(),
+                Some("non-fast-forward") | Some("fetch first")
+            )
+    });
+
+    Ok(PushResult {
+        remote,
+        success: output.status.success(),
+        rejected,
+        non_fast_forward,
+        upstream_set: upstream_set && output.status.success(),
+        updates,
+        message: stderr.trim().to_string(),
+    })
+}
+
+#[derive(serde::Serialize)]
+pub struct FetchRefUpdate {
+    pub kind: String,
+    pub source: String,
+    pub from_ref: String,
+    pub to_ref: String,
+    pub old_oid: Option<String>,
+    pub new_oid: Option<String>,
+    pub reason: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+pub struct FetchResult {
+    pub remotes: Vec<String>,
+    pub updates: Vec<FetchRefUpdate>,
+}
+
+/// Parses the ref-update lines git fetch writes to stderr, e.g.
+/// ` + 1a2b3c4...5d6e7f8 main       -> origin/main  (forced update)`.
+fn parse_fetch_output(stderr: &str) -> Vec<FetchRefUpdate> {
+    let mut updates = Vec::new();
+    let mut source = String::new();
+
+    for line in stderr.lines() {
+        if let Some(url) = line.strip_prefix("From ") {
+            source = url.trim().to_string();
+            continue;
+        }
+
+        let mut chars = line.chars();
+        let (Some(' '), Some(flag), Some(' ')) = (chars.next(), chars.next(), chars.next()) else {
+            continue;
+        };
+        let rest = chars.as_str().trim_start();
+
+        let (summary, rest) = if rest.starts_with('[') {
+            match rest.split_once(']') {
+                Some((summary, rest)) => (&summary[1..], rest),
+                None => continue,
+            }
+        } else {
+            match rest.split_once(' ') {
+                Some((summary, rest)) => (summary, rest),
+                None => continue,
+            }
+        };
+
+        let Some((from_ref, rest)) = rest.split_once(" -> ") else {
+            continue;
+        };
+        let rest = rest.trim();
+        let (to_ref, reason) = match rest.split_once(char::is_whitespace) {
+            Some((to_ref, reason)) => (
+                to_ref,
+                Some(reason.trim().trim_start_matches('(').trim_end_matches(')')),
+            ),
+            None => (rest, None),
+        };
+
+        let kind = match (flag, summary) {
+            ('*', _) => "created",
+            (' ', _) => "updated",
+            ('+', _) => "forced",
+            ('-', _) => "deleted",
+            ('t', _) => "tag-updated",
+            ('=', _) => "up-to-date",
+            ('!', _) => "rejected",
+            _ => continue,
+        }
+        .to_string();
+
+        let (old_oid, new_oid) = match summary.split_once("..") {
+            Some((old, new)) => (
+                Some(old.to_string()),
+                Some(new.trim_start_matches('.').to_string()),
+            ),
+            None => (None, None),
+        };
+
+        updates.push(FetchRefUpdate {
+            kind,
+            source: source.clone(),
+            from_ref: from_ref.trim().to_string(),
+            to_ref: to_ref.to_string(),
+            old_oid,
+            new_oid,
+            reason: reason.filter(|r| !r.is_empty()).map(|r| r.to_string()),
+        });
+    }
+
+    updates
+}
+
+#[tauri::command]
+pub async fn fetch_git_remote(
+    repo_path: String,
+    remote: Option<String>,
+    prune: Option<bool>,
+    tags: Option<bool>,
+) -> Result<FetchResult, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let remotes_output = Command::new("git")
+        .arg("remote")
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git remote: {}", e))?;
+
+    if !remotes_output.status.success() {
+        let stderr = String::from_utf8_lossy(&remotes_output.stderr);
+        return Err(format!("Git remote failed: {}", stderr));
+    }
+
+    let all_remotes: Vec<String> = String::from_utf8_lossy(&remotes_output.stdout)
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect();
+
+    let remote = remote
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty());
+
+    let remotes = match &remote {
+        Some(name) if !all_remotes.contains(name) => {
+            return Err(format!("No such remote '{}'", name));
+        }
+        Some(name) => vec![name.clone()],
+        None => all_remotes,
+    };
+
+    if remotes.is_empty() {
+        return Ok(FetchResult {
+            remotes,
+            updates: Vec::new(),
+        });
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.arg("fetch").arg("--no-progress");
+
+    if prune.unwrap_or(false) {
+        cmd.arg("--prune");
+    }
+
+    if tags.unwrap_or(false) {
+        cmd.arg("--tags");
+    }
+
+    match &remote {
+        Some(name) => cmd.arg(name),
+        None => cmd.arg("--all"),
+    };
+
+    let output = cmd
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("LC_ALL", "C")
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git fetch: {}", e))?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    if !output.status.success() {
+        return Err(format!("Git fetch failed: {}", stderr));
+    }
+
+    Ok(FetchResult {
+        remotes,
+        updates: parse_fetch_output(&stderr),
+    })
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct CommitRef {
+    pub name: String,
+    pub full_name: String,
+    pub kind: String,
+    pub is_head: bool,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct Trailer {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct Identity {
+    pub name: String,
+    pub email: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct Commit {
+    pub hash: String,
+    pub parents: Vec<String>,
+    pub refs: Vec<CommitRef>,
+    pub author: String,
+    pub email: String,
+    pub date: String,
+    pub message: String,
+    pub body: String,
+    pub trailers: Vec<Trailer>,
+    pub co_authors: Vec<Identity>,
+}
+
+/// `git log` format read by `parse_commit_log`; use with `--decorate=full`
+/// so decorations carry full ref names. Trailers come from git itself so they
+/// follow `git interpret-trailers` rules, with continuation lines unfolded.
+pub(crate) const COMMIT_LOG_FORMAT: &str = "--pretty=format:%H%x1E%P%x1E%D%x1E%an%x1E%ae%x1E%ai\
+     %x1E%s%x1E%b%x1E%(trailers:only,unfold,separator=%x1D,key_value_separator=%x1C)%x1F";
+
+/// Passed before `--all` so reset snapshots under `refs/git-client/` and the
+/// stash are not listed as history.
+pub(crate) const ALL_REFS_EXCLUDES: [&str; 2] =
+    ["--exclude=refs/git-client/*", "--exclude=refs/stash"];
+
+/// Parses a `%D` decoration list such as
+/// `HEAD -> refs/heads/main, tag: refs/tags/v1, refs/remotes/origin/main`.
+pub(crate) fn parse_decorations(decorations: &str) -> Vec<CommitRef> {
+    let mut refs = Vec::new();
+
+    for decoration in decorations.split(", ").map(str::trim) {
+        if decoration.is_empty() {
+            continue;
+        }
+
+        let (full_name, is_head) = match decoration.strip_prefix("HEAD -> ") {
+            Some(name) => (name, true),
+            None => (
+                decoration.strip_prefix("tag: ").unwrap_or(decoration),
+                false,
+            ),
+        };
+
+        let (kind, name) = if full_name == "HEAD" {
+            ("head", "HEAD")
+        } else if let Some(name) = full_name.strip_prefix("refs/heads/") {
+            ("local", name)
+        } else if let Some(name) = full_name.strip_prefix("refs/remotes/") {
+            ("remote", name)
+        } else if let Some(name) = full_name.strip_prefix("refs/tags/") {
+            ("tag", name)
+        } else {
+            ("other", full_name)
+        };
+
+        refs.push(CommitRef {
+            name: name.to_string(),
+            full_name: full_name.to_string(),
+            kind: kind.to_string(),
+            is_head: is_head || full_name == "HEAD",
+        });
+    }
+
+    refs
+}
+
+/// Parses trailers formatted as `key<FS>value` pairs separated by `<GS>`.
+pub(crate) fn parse_trailers(raw: &str) -> Vec<Trailer> {
+    raw.split('\u{001D}')
+        .filter_map(|trailer| trailer.split_once('\u{001C}'))
+        .map(|(key, value)| Trailer {
+            key: key.trim().to_string(),
+            value: value.trim().to_string(),
+        })
+        .filter(|trailer| !trailer.key.is_empty())
+        .collect()
+}
+
+/// Parses a `Name <email>` identity as used by `Co-authored-by`.
+pub(crate) fn parse_identity(value: &str) -> Option<Identity> {
+    let (name, rest) = value.split_once('<')?;
+    let email = rest.split_once('>')?.0.trim();
+
+    Some(Identity {
+        name: name.trim().to_string(),
+        email: email.to_string(),
+    })
+}
+
+pub(crate) fn co_authors(trailers: &[Trailer]) -> Vec<Identity> {
+    trailers
+        .iter()
+        .filter(|trailer| trailer.key.eq_ignore_ascii_case("co-authored-by"))
+        .filter_map(|trailer| parse_identity(&trailer.value))
+        .collect()
+}
+
+/// Parses `git log` output produced with `COMMIT_LOG_FORMAT`.
+pub(crate) fn parse_commit_log(stdout: &str) -> Vec<Commit> {
+    let record_separator = '\u{001E}';
+    let unit_separator = '\u{001F}';
+    let mut commits = Vec::new();
+
+    for record in stdout.split(unit_separator) {
+        if record.trim().is_empty() {
+            continue;
+        }
+
+        let parts: Vec<&str> = record.split(record_separator).collect();
+        if parts.len() < 9 {
+            continue;
+        }
+
+        let trailers = parse_trailers(parts[8].trim());
+
+        commits.push(Commit {
+            hash: parts[0].trim().to_string(),
+            parents: parts[1].split_whitespace().map(|p| p.to_string()).collect(),
+            refs: parse_decorations(parts[2]),
+            author: parts[3].trim().to_string(),
+            email: parts[4].trim().to_string(),
+            date: parts[5].trim().to_string(),
+            message: parts[6].trim().to_string(),
+            body: parts[7].trim_end().to_string(),
+            co_authors: co_authors(&trailers),
+            trailers,
+        });
+    }
+
+    commits
+}
+
+/// Filters for `query_git_commits`. Without `refs` or `all` the log starts
+/// at HEAD; pattern fields use git's regex matching.
+#[derive(Default, serde::Deserialize)]
+pub struct CommitFilter {
+    #[serde(default)]
+    pub refs: Vec<String>,
+    #[serde(default)]
+    pub all: bool,
+    pub author: Option<String>,
+    pub committer: Option<String>,
+    pub message: Option<String>,
+    #[serde(default)]
+    pub ignore_case: bool,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    #[serde(default)]
+    pub paths: Vec<String>,
+    #[serde(default)]
+    pub follow: bool,
+    #[serde(default)]
+    pub merges_only: bool,
+    #[serde(default)]
+    pub no_merges: bool,
+    #[serde(default)]
+    pub first_parent: bool,
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+async fn log_commits(
+    repo: &Path,
+    filter: &CommitFilter,
+    skip: Option<u32>,
+    limit: Option<u32>,
+) -> Result<Vec<Commit>, String> {
+    if filter.merges_only && filter.no_merges {
+        return Err("Merges only cannot be combined with no merges".to_string());
+    }
+
+    let paths: Vec<String> = filter
+        .paths
+        .iter()
+        .map(|p| {
+            Path::new(p)
+                .strip_prefix(repo)
+                .ok()
+                .and_then(|p| p.to_str())
+                .unwrap_or(p)
+                .to_string()
+        })
+        .filter(|p| !p.is_empty())
+        .collect();
+
+    if filter.follow && paths.len() != 1 {
+        return Err("Following renames requires exactly one file path".to_string());
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.arg("--no-pager")
+        .arg("log")
+        .arg("--decorate=full")
+        .arg(COMMIT_LOG_FORMAT);
 
-        let parts: Vec<&str> = record.split(record_separator).collect();
-        if parts.len() >= 5 {
-            if skipped < skip_count {
-                skipped += 1;
-                continue;
-            }
+    if let Some(author) = non_empty(&filter.author) {
+        cmd.arg(format!("--author={}", author));
+    }
 
-            if let Some(limit_val) = limit {
-                if commits.len() >= limit_val as usize {
-                    break;
-                }
-            }
+    if let Some(committer) = non_empty(&filter.committer) {
+        cmd.arg(format!("--committer={}", committer));
+    }
 
-            let hash = parts[0].trim().to_string();
-            let author = parts[1].trim().to_string();
-            let email = parts[2].trim().to_string();
-            let date_str = parts[3].trim().to_string();
-            let message = parts[4..]
-                .join(&record_separator.to_string())
-                .trim()
-                .to_string();
-
-            commits.push(Commit {
-                hash,
-                author,
-                email,
-                date: date_str,
-                message,
-            });
+    if let Some(message) = non_empty(&filter.message) {
+        cmd.arg(format!("--grep={}", message));
+    }
+
+    if filter.ignore_case {
+        cmd.arg("--regexp-ignore-case");
+    }
+
+    if let Some(since) = non_empty(&filter.since) {
+        cmd.arg(format!("--since={}", since));
+    }
+
+    if let Some(until) = non_empty(&filter.until) {
+        cmd.arg(format!("--until={}", until));
+    }
+
+    if filter.merges_only {
+        cmd.arg("--merges");
+    }
+
+    if filter.no_merges {
+        cmd.arg("--no-merges");
+    }
+
+    if filter.first_parent {
+        cmd.arg("--first-parent");
+    }
+
+    if filter.follow {
+        cmd.arg("--follow");
+    }
+
+    if let Some(limit_val) = limit {
+        let total_needed = skip.unwrap_or(0) + limit_val;
+        cmd.arg("-n").arg(total_needed.to_string());
+    }
+
+    if filter.all {
+        cmd.args(ALL_REFS_EXCLUDES).arg("--all");
+    }
+
+    for reference in filter
+        .refs
+        .iter()
+        .map(|r| r.trim())
+        .filter(|r| !r.is_empty())
+    {
+        if reference.starts_with('-') {
+            return Err(format!("Invalid ref '{}'", reference));
         }
+        cmd.arg(reference);
     }
 
+    cmd.arg("--").args(&paths);
+
+    let output = cmd
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git log: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git log command failed: {}", stderr));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    if stdout.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let commits = parse_commit_log(&stdout)
+        .into_iter()
+        .skip(skip.unwrap_or(0) as usize)
+        .take(limit.map(|l| l as usize).unwrap_or(usize::MAX))
+        .collect();
+
     Ok(commits)
 }
 
-#[derive(serde::Serialize)]
+#[tauri::command]
+pub async fn get_git_commits(
+    repo_path: String,
+    skip: Option<u32>,
+    limit: Option<u32>,
+) -> Result<Vec<Commit>, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    log_commits(repo, &CommitFilter::default(), skip, limit).await
+}
+
+#[tauri::command]
+pub async fn query_git_commits(
+    repo_path: String,
+    filter: CommitFilter,
+    skip: Option<u32>,
+    limit: Option<u32>,
+) -> Result<Vec<Commit>, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    log_commits(repo, &filter, skip, limit).await
+}
+
+#[derive(serde::Serialize, Default)]
 pub struct ChangedFile {
     pub status: String,
     pub path: String,
+    pub old_path: Option<String>,
     pub additions: Option<u32>,
     pub deletions: Option<u32>,
+    pub binary: bool,
+    pub old_mode: Option<String>,
+    pub new_mode: Option<String>,
+    pub similarity: Option<u32>,
+}
+
+#[derive(serde::Serialize, Default)]
+pub struct DiffTotals {
+    pub files: u32,
+    pub insertions: u32,
+    pub deletions: u32,
 }
 
 #[derive(serde::Serialize)]
 pub struct CommitDetails {
     pub hash: String,
+    pub tree: String,
+    pub parents: Vec<String>,
+    pub refs: Vec<CommitRef>,
     pub author: String,
     pub email: String,
     pub date: String,
+    pub author_timestamp: i64,
+    pub author_tz_offset: i32,
+    pub committer: String,
+    pub committer_email: String,
+    pub committer_date: String,
+    pub committer_timestamp: i64,
+    pub committer_tz_offset: i32,
     pub message: String,
+    pub body: String,
+    pub trailers: Vec<Trailer>,
+    pub co_authors: Vec<Identity>,
     pub files: Vec<ChangedFile>,
-    pub stats: String,
+    pub stats: DiffTotals,
+}
+
+/// NUL-separated header read by `get_commit_details`. The diff output
+/// follows the last field.
+const COMMIT_DETAILS_FORMAT: &str = "--format=%H%x00%T%x00%P%x00%an%x00%ae%x00%ai%x00%at\
+     %x00%cn%x00%ce%x00%ci%x00%ct%x00%D%x00%s%x00%b\
+     %x00%(trailers:only,unfold,separator=%x1D,key_value_separator=%x1C)%x00";
+const COMMIT_DETAILS_FIELDS: usize = 15;
+
+/// Converts the `+hhmm` offset at the end of an ISO date into minutes.
+fn tz_offset_minutes(date: &str) -> i32 {
+    let Some(offset) = date.split_whitespace().last() else {
+        return 0;
+    };
+
+    let (sign, digits) = match offset.split_at_checked(1) {
+        Some(("-", digits)) => (-1, digits),
+        Some(("+", digits)) => (1, digits),
+        _ => return 0,
+    };
+
+    let hours: i32 = digits.get(..2).and_then(|h| h.parse().ok()).unwrap_or(0);
+    let minutes: i32 = digits.get(2..4).and_then(|m| m.parse().ok()).unwrap_or(0);
+    sign * (hours * 60 + minutes)
+}
+
+/// Parses the `--raw --numstat -z` output that follows the commit header.
+/// Git prints every raw entry before the numstat entries, in the same order.
+/// Renames and copies report their new path in `path`.
+pub(crate) fn parse_raw_numstat(section: &str) -> (Vec<ChangedFile>, DiffTotals) {
+    let mut files = Vec::new();
+    let mut numstat = 0;
+    let mut tokens = section.split('\0');
+
+    while let Some(token) = tokens.next() {
+        let token = token.trim_start_matches('\n');
+
+        if let Some(meta) = token.strip_prefix(':') {
+            let parts: Vec<&str> = meta.split_whitespace().collect();
+            let Some(status) = parts.get(4) else {
+                continue;
+            };
+            let status_char = status.chars().next().unwrap_or('X');
+            let mode = |m: &&str| Some(m.to_string()).filter(|m| m != "000000");
+
+            let mut file = ChangedFile {
+                status: status_code_name(status_char).to_string(),
+                path: tokens.next().unwrap_or_default().to_string(),
+                old_mode: parts.first().and_then(mode),
+                new_mode: parts.get(1).and_then(mode),
+                similarity: status[1..].parse().ok(),
+                ..Default::default()
+            };
+
+            if status_char == 'R' || status_char == 'C' {
+                let new_path = tokens.next().unwrap_or_default().to_string();
+                file.old_path = Some(std::mem::replace(&mut file.path, new_path));
+            }
+
+            files.push(file);
+            continue;
+        }
+
+        let mut counts = token.splitn(3, '\t');
+        let (Some(additions), Some(deletions), Some(path)) =
+            (counts.next(), counts.next(), counts.next())
+        else {
+            continue;
+        };
+
+        // Renames and copies leave the path empty and list both paths after it.
+        if path.is_empty() {
+            tokens.next();
+            tokens.next();
+        }
+
+        if let Some(file) = files.get_mut(numstat) {
+            // Binary files are reported as `-\t-`.
+            file.binary = additions == "-" && deletions == "-";
+            file.additions = additions.parse().ok();
+            file.deletions = deletions.parse().ok();
+        }
+        numstat += 1;
+    }
+
+    let totals = DiffTotals {
+        files: files.len() as u32,
+        insertions: files.iter().filter_map(|f| f.additions).sum(),
+        deletions: files.iter().filter_map(|f| f.deletions).sum(),
+    };
+
+    (files, totals)
 }
 
 #[tauri::command]
@@ -548,138 +1994,74 @@ pub async fn get_commit_details(
         return Err("Not a git repository".to_string());
     }
 
-    let commit_output = Command::new("git")
+    // Merges are shown against their first parent.
+    let output = Command::new("git")
+        .arg("--no-pager")
         .arg("show")
-        .arg("--pretty=format:{%n  \"hash\": \"%H\",%n  \"author\": \"%an\",%n  \"email\": \"%ae\",%n  \"date\": \"%ad\",%n  \"message\": \"%s\"%n}")
-        .arg("--date=iso")
-        .arg("--no-patch")
+        .arg("-z")
+        .arg("--no-color")
+        .arg("--decorate=full")
+        .arg(COMMIT_DETAILS_FORMAT)
+        .arg("--raw")
+        .arg("--numstat")
+        .arg("--find-renames")
+        .arg("--find-copies")
+        .arg("--diff-merges=first-parent")
+        .arg("--end-of-options")
         .arg(&commit_hash)
+        .arg("--")
         .current_dir(repo)
         .output()
         .await
         .map_err(|e| format!("Failed to execute git show: {}", e))?;
 
-    if !commit_output.status.success() {
-        let stderr = String::from_utf8_lossy(&commit_output.stderr);
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
         return Err(format!("Git show command failed: {}", stderr));
     }
 
-    let commit_stdout = String::from_utf8_lossy(&commit_output.stdout);
-    let commit_json = commit_stdout.trim_end_matches(',').trim();
-
-    let commit: Commit = serde_json::from_str(commit_json)
-        .map_err(|e| format!("Failed to parse commit info: {}", e))?;
-
-    let name_status_output = Command::new("git")
-        .arg("diff-tree")
-        .arg("--no-commit-id")
-        .arg("--name-status")
-        .arg("-r")
-        .arg(&commit_hash)
-        .current_dir(repo)
-        .output()
-        .await
-        .map_err(|e| format!("Failed to execute git diff-tree: {}", e))?;
-
-    if !name_status_output.status.success() {
-        let stderr = String::from_utf8_lossy(&name_status_output.stderr);
-        return Err(format!("Git diff-tree command failed: {}", stderr));
-    }
-
-    let stat_output = Command::new("git")
-        .arg("show")
-        .arg("--stat")
-        .arg("--format=")
-        .arg(&commit_hash)
-        .current_dir(repo)
-        .output()
-        .await
-        .map_err(|e| format!("Failed to execute git show --stat: {}", e))?;
-
-    if !stat_output.status.success() {
-        let stderr = String::from_utf8_lossy(&stat_output.stderr);
-        return Err(format!("Git show --stat command failed: {}", stderr));
-    }
-
-    let name_status_stdout = String::from_utf8_lossy(&name_status_output.stdout);
-    let name_status_lines: Vec<&str> = name_status_stdout.lines().collect();
-
-    let mut files: Vec<ChangedFile> = Vec::new();
-
-    for line in &name_status_lines {
-        let trimmed = line.trim();
-
-        if trimmed.is_empty() {
-            continue;
-        }
-
-        if trimmed.len() >= 2 {
-            let status_char = trimmed.chars().next().unwrap();
-            let rest = trimmed[1..].trim_start();
-
-            if (status_char == 'A'
-                || status_char == 'M'
-                || status_char == 'D'
-                || status_char == 'R'
-                || status_char == 'C')
-                && !rest.is_empty()
-            {
-                let file_path = if status_char == 'R' || status_char == 'C' {
-                    rest.split('\t').nth(1).unwrap_or(rest).trim().to_string()
-                } else {
-                    rest.to_string()
-                };
-
-                if !file_path.is_empty() {
-                    let status = match status_char {
-                        'A' => "added",
-                        'M' => "modified",
-                        'D' => "deleted",
-                        'R' => "renamed",
-                        'C' => "copied",
-                        _ => "unknown",
-                    }
-                    .to_string();
-
-                    files.push(ChangedFile {
-                        status,
-                        path: file_path,
-                        additions: None,
-                        deletions: None,
-                    });
-                }
-            }
-        }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let fields: Vec<&str> = stdout.splitn(COMMIT_DETAILS_FIELDS + 1, '\0').collect();
+    if fields.len() < COMMIT_DETAILS_FIELDS {
+        return Err("Failed to parse commit info".to_string());
     }
 
-    let stat_stdout = String::from_utf8_lossy(&stat_output.stdout);
-    let stat_lines: Vec<&str> = stat_stdout.lines().collect();
-    let mut stats = String::new();
-
-    for line in &stat_lines {
-        let trimmed = line.trim();
-        if trimmed.contains("file changed")
-            || trimmed.contains("insertion")
-            || trimmed.contains("deletion")
-        {
-            stats.push_str(trimmed);
-            stats.push('\n');
-        }
-    }
+    let (files, stats) = parse_raw_numstat(fields.get(COMMIT_DETAILS_FIELDS).unwrap_or(&""));
+    let trailers = parse_trailers(fields[14].trim());
 
     Ok(CommitDetails {
-        hash: commit.hash,
-        author: commit.author,
-        email: commit.email,
-        date: commit.date,
-        message: commit.message,
+        hash: fields[0].to_string(),
+        tree: fields[1].to_string(),
+        parents: fields[2]
+            .split_whitespace()
+            .map(|p| p.to_string())
+            .collect(),
+        refs: parse_decorations(fields[11]),
+        author: fields[3].to_string(),
+        email: fields[4].to_string(),
+        date: fields[5].to_string(),
+        author_timestamp: fields[6].parse().unwrap_or(0),
+        author_tz_offset: tz_offset_minutes(fields[5]),
+        committer: fields[7].to_string(),
+        committer_email: fields[8].to_string(),
+        committer_date: fields[9].to_string(),
+        committer_timestamp: fields[10].parse().unwrap_or(0),
+        committer_tz_offset: tz_offset_minutes(fields[9]),
+        message: fields[12].trim().to_string(),
+        body: fields[13].trim_end().to_string(),
+        co_authors: co_authors(&trailers),
+        trailers,
         files,
-        stats: stats.trim().to_string(),
+        stats,
     })
 }
 
 #[tauri::command]
-pub async fn get_git_diff(repo_path: String, file_path: String) -> Result<String, String> {
+pub async fn get_git_diff(
+    repo_path: String,
+    file_path: String,
+    context_lines: Option<u32>,
+) -> Result<Vec<FileDiff>, String> {
     let repo = Path::new(&repo_path);
 
     if !repo.exists() {
@@ -701,8 +2083,9 @@ pub async fn get_git_diff(repo_path: String, file_path: String) -> Result<String
     let output = Command::new("git")
         .arg("--no-pager")
         .arg("diff")
-        .arg("--no-color")
-        .arg("-U999999")
+        .args(PATCH_FORMAT_ARGS)
+        .arg(unified_arg(context_lines))
+        .arg("--")
         .arg(relative_path)
         .current_dir(repo)
         .output()
@@ -715,7 +2098,7 @@ pub async fn get_git_diff(repo_path: String, file_path: String) -> Result<String
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    Ok(stdout.to_string())
+    Ok(parse_unified_diff(&stdout))
 }
 
 #[tauri::command]
@@ -765,6 +2148,8 @@ pub async fn stage_file(repo_path: String, file_path: String) -> Result<(), Stri
         return Err("Not a git repository".to_string());
     }
 
+    let before = capture_state(repo).await;
+
     let output = Command::new("git")
         .arg("add")
         .arg(&file_path)
@@ -778,6 +2163,7 @@ pub async fn stage_file(repo_path: String, file_path: String) -> Result<(), Stri
         return Err(format!("Git add failed: {}", stderr));
     }
 
+    record_operation(repo, "stage", false, before).await;
     Ok(())
 }
 
@@ -794,6 +2180,8 @@ pub async fn unstage_file(repo_path: String, file_path: String) -> Result<(), St
         return Err("Not a git repository".to_string());
     }
 
+    let before = capture_state(repo).await;
+
     let output = Command::new("git")
         .arg("restore")
         .arg("--staged")
@@ -808,6 +2196,7 @@ pub async fn unstage_file(repo_path: String, file_path: String) -> Result<(), St
         return Err(format!("Git restore --staged failed: {}", stderr));
     }
 
+    record_operation(repo, "unstage", false, before).await;
     Ok(())
 }
 
@@ -831,6 +2220,8 @@ pub async fn commit_changes(
         return Err("Commit message cannot be empty".to_string());
     }
 
+    let before = capture_state(repo).await;
+
     let output = Command::new("git")
         .arg("commit")
         .arg("-m")
@@ -845,6 +2236,931 @@ pub async fn commit_changes(
         return Err(format!("Git commit failed: {}", stderr));
     }
 
+    // Committing a conflicted merge, cherry-pick or revert completes it, so
+    // journal the whole operation. While a cherry-pick or revert of several
+    // commits is still in progress it is journaled once it is continued.
+    let in_sequence = git_dir.join("sequencer").exists();
+    let finished = in_sequence
+        || finish_pending(repo, "merge").await
+        || finish_pending(repo, "cherry-pick").await
+        || finish_pending(repo, "revert").await;
+
+    if !finished {
+        record_operation(repo, "commit", false, before).await;
+    }
+
     let stdout = String::from_utf8_lossy(&output.stdout);
     Ok(stdout.trim().to_string())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_util::{commit_file, git, init_repo, path_str, write_file};
+    use tempfile::TempDir;
+
+    const OID: &str = "78981922613b2afb6025042ff6bd878ac1994e85";
+
+    #[test]
+    fn derives_clone_directory_names() {
+        let cases = [
+            ("https://github.com/user/repo.git", Some("repo")),
+            ("https://github.com/user/repo", Some("repo")),
+            ("https://github.com/user/repo.git/", Some("repo")),
+            ("https://github.com/user/repo//", Some("repo")),
+            ("/srv/git/repo/.git", Some("repo")),
+            ("C:\\src\\repo\\", Some("repo")),
+            ("git@github.com:user/repo.git", Some("repo")),
+            ("git@host:repo.git", Some("repo")),
+            ("  ssh://host:2222/srv/project.git  ", Some("project")),
+            ("git@host:", None),
+            ("", None),
+        ];
+
+        for (url, expected) in cases {
+            assert_eq!(clone_directory_name(url).as_deref(), expected, "{}", url);
+        }
+    }
+
+    #[test]
+    fn parses_clone_progress_lines() {
+        let progress = parse_clone_progress(
+            "/tmp/repo",
+            "Receiving objects:  45% (450/1000), 1.20 MiB | 512.00 KiB/s",
+        );
+        assert_eq!(progress.target_path, "/tmp/repo");
+        assert_eq!(progress.phase, "Receiving objects");
+        assert_eq!(progress.percent, Some(45));
+        assert_eq!(progress.current, Some(450));
+        assert_eq!(progress.total, Some(1000));
+
+        let progress =
+            parse_clone_progress("/tmp/repo", "remote: Counting objects: 100% (12/12), done.");
+        assert_eq!(progress.phase, "Counting objects");
+        assert_eq!(progress.percent, Some(100));
+        assert_eq!(progress.total, Some(12));
+
+        let progress = parse_clone_progress("/tmp/repo", "Cloning into '/tmp/repo'...");
+        assert_eq!(progress.phase, "Cloning into '/tmp/repo'...");
+        assert_eq!(progress.percent, None);
+        assert_eq!(progress.current, None);
+    }
+
+    #[tokio::test]
+    async fn clones_a_local_bare_repository() {
+        let (repo, remote) = repo_with_remote();
+        git(repo.path(), &["push", "-q", "origin", "main"]);
+        let head = git(repo.path(), &["rev-parse", "HEAD"]);
+
+        let target = TempDir::new().unwrap();
+        let url = format!("{}/", remote.path().display());
+        let mut progress = Vec::new();
+
+        let cloned = clone_repo(url.clone(), path_str(&target), None, Some(1), |p| {
+            progress.push(p)
+        })
+        .await
+        .unwrap();
+
+        let name = remote.path().file_name().unwrap().to_string_lossy();
+        assert_eq!(Path::new(&cloned), target.path().join(name.as_ref()));
+        assert_eq!(git(Path::new(&cloned), &["rev-parse", "HEAD"]), head);
+        assert!(Path::new(&cloned).join("a.txt").exists());
+        assert!(progress.iter().all(|p| p.target_path == cloned));
+        assert!(progress.iter().any(|p| p.phase.starts_with("Cloning into")));
+
+        let again = clone_repo(url, path_str(&target), None, None, |_| {}).await;
+        assert!(matches!(again, Err(e) if e.contains("not empty")));
+    }
+
+    #[test]
+    fn parses_status_branch_headers() {
+        let stdout = format!(
+            "# branch.oid {OID}\0# branch.head feature\0\
+             # branch.upstream origin/feature\0# branch.ab +2 -3\0"
+        );
+
+        let status = parse_status_v2(&stdout);
+        assert_eq!(status.branch.oid.as_deref(), Some(OID));
+        assert_eq!(status.branch.head.as_deref(), Some("feature"));
+        assert_eq!(status.branch.upstream.as_deref(), Some("origin/feature"));
+        assert_eq!(status.branch.ahead, 2);
+        assert_eq!(status.branch.behind, 3);
+        assert!(status.entries.is_empty());
+    }
+
+    #[test]
+    fn parses_initial_and_detached_heads_as_none() {
+        let status = parse_status_v2("# branch.oid (initial)\0# branch.head (detached)\0");
+        assert_eq!(status.branch.oid, None);
+        assert_eq!(status.branch.head, None);
+    }
+
+    #[test]
+    fn parses_status_entries() {
+        // Captured from a repository mid-merge with a staged rename.
+        let stdout = format!(
+            "# branch.oid {OID}\0# branch.head main\0\
+             1 .M N... 100644 100644 100644 {OID} {OID} mod.txt\0\
+             2 R. N... 100644 100644 100644 {OID} {OID} R100 new name.txt\0old.txt\0\
+             2 C. N... 100644 100644 100644 {OID} {OID} C75 copy.txt\0src.txt\0\
+             u UU N... 100644 100644 100644 100644 {OID} {OID} {OID} conf.txt\0\
+             ? un tracked.txt\0\
+             ! target/out.o\0"
+        );
+
+        let status = parse_status_v2(&stdout);
+        let entries = &status.entries;
+        assert_eq!(entries.len(), 6);
+
+        assert_eq!(entries[0].kind, "changed");
+        assert_eq!(entries[0].path, "mod.txt");
+        assert_eq!(entries[0].index_status, "unmodified");
+        assert_eq!(entries[0].worktree_status, "modified");
+        assert!(entries[0].conflict.is_none());
+        assert!(entries[0].submodule.is_none());
+
+        assert_eq!(entries[1].kind, "renamed");
+        assert_eq!(entries[1].path, "new name.txt");
+        assert_eq!(entries[1].original_path.as_deref(), Some("old.txt"));
+        assert_eq!(entries[1].index_status, "renamed");
+        assert_eq!(entries[1].similarity, Some(100));
+
+        assert_eq!(entries[2].kind, "copied");
+        assert_eq!(entries[2].path, "copy.txt");
+        assert_eq!(entries[2].original_path.as_deref(), Some("src.txt"));
+        assert_eq!(entries[2].similarity, Some(75));
+
+        assert_eq!(entries[3].kind, "unmerged");
+        assert_eq!(entries[3].path, "conf.txt");
+        assert_eq!(entries[3].index_status, "unmerged");
+        assert_eq!(entries[3].conflict.as_deref(), Some("both-modified"));
+
+        assert_eq!(entries[4].kind, "untracked");
+        assert_eq!(entries[4].path, "un tracked.txt");
+
+        assert_eq!(entries[5].kind, "ignored");
+        assert_eq!(entries[5].path, "target/out.o");
+    }
+
+    #[test]
+    fn parses_unmerged_conflict_types() {
+        let line =
+            |xy: &str| format!("u {xy} N... 100644 100644 100644 100644 {OID} {OID} {OID} f\0");
+        let stdout: String = ["DD", "AU", "UD", "UA", "DU", "AA"]
+            .iter()
+            .map(|xy| line(xy))
+            .collect();
+
+        let conflicts: Vec<String> = parse_status_v2(&stdout)
+            .entries
+            .into_iter()
+            .filter_map(|entry| entry.conflict)
+            .collect();
+        assert_eq!(
+            conflicts,
+            [
+                "both-deleted",
+                "added-by-us",
+                "deleted-by-them",
+                "added-by-them",
+                "deleted-by-us",
+                "both-added"
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_submodule_state() {
+        let stdout = format!("1 .M SC.U 160000 160000 160000 {OID} {OID} vendor/lib\0");
+
+        let status = parse_status_v2(&stdout);
+        let submodule = status.entries[0].submodule.as_ref().unwrap();
+        assert!(submodule.commit_changed);
+        assert!(!submodule.tracked_changes);
+        assert!(submodule.untracked_changes);
+    }
+
+    #[tokio::test]
+    async fn reads_status_of_a_real_repository() {
+        let repo = init_repo();
+        commit_file(repo.path(), "old.txt", "1\n2\n3\n", "first");
+        git(repo.path(), &["mv", "old.txt", "new.txt"]);
+        write_file(repo.path(), "new file.txt", "x\n");
+
+        let status = get_git_status(path_str(&repo)).await.unwrap();
+        assert_eq!(status.branch.head.as_deref(), Some("main"));
+
+        let renamed = status.entries.iter().find(|e| e.kind == "renamed").unwrap();
+        assert_eq!(renamed.path, "new.txt");
+        assert_eq!(renamed.original_path.as_deref(), Some("old.txt"));
+
+        let untracked = status
+            .entries
+            .iter()
+            .find(|e| e.kind == "untracked")
+            .unwrap();
+        assert_eq!(untracked.path, "new file.txt");
+    }
+
+    #[tokio::test]
+    async fn branch_commands_create_rename_and_delete() {
+        let repo = init_repo();
+        let first = commit_file(repo.path(), "a.txt", "one\n", "first");
+        commit_file(repo.path(), "a.txt", "two\n", "second");
+
+        create_git_branch(
+            path_str(&repo),
+            "topic".into(),
+            Some(first.clone()),
+            Some(true),
+        )
+        .await
+        .unwrap();
+        assert_eq!(git(repo.path(), &["branch", "--show-current"]), "topic");
+        assert_eq!(git(repo.path(), &["rev-parse", "HEAD"]), first);
+
+        rename_git_branch(path_str(&repo), "topic".into(), "renamed".into(), None)
+            .await
+            .unwrap();
+        set_branch_upstream(path_str(&repo), "renamed".into(), Some("main".into()))
+            .await
+            .unwrap();
+        assert_eq!(
+            git(
+                repo.path(),
+                &["rev-parse", "--abbrev-ref", "renamed@{upstream}"]
+            ),
+            "main"
+        );
+
+        git(repo.path(), &["checkout", "-q", "main"]);
+        delete_git_branch(path_str(&repo), "renamed".into(), Some(true), None)
+            .await
+            .unwrap();
+        assert_eq!(git(repo.path(), &["branch", "--list", "renamed"]), "");
+    }
+
+    /// `for-each-ref` records for a clone with a local `origin/main` that
+    /// collides with the remote branch, a `team/up` remote, a branch of a
+    /// removed remote and a ref directly under `refs/remotes/`.
+    const BRANCH_REFS: &str = "\
+        refs/heads/feature/x\x1Erefs/remotes/origin/feature/x\x1Eahead 1\x1E74f148da\x1Etwo\x1E2024-01-02 03:04:05 +0000\x1E \x1E\x1F\n\
+        refs/heads/gone\x1Erefs/remotes/origin/gone\x1Egone\x1E0020c2ab\x1Eone\x1E2024-01-02 03:04:05 +0000\x1E \x1E\x1F\n\
+        refs/heads/main\x1Erefs/remotes/origin/main\x1Eahead 2, behind 3\x1E0020c2ab\x1Eone\x1E2024-01-02 03:04:05 +0000\x1E*\x1E\x1F\n\
+        refs/heads/origin/main\x1E\x1E\x1E0020c2ab\x1Eone\x1E2024-01-02 03:04:05 +0000\x1E \x1E\x1F\n\
+        refs/remotes/old-remote/topic\x1E\x1E\x1E0020c2ab\x1Eone\x1E2024-01-02 03:04:05 +0000\x1E \x1E\x1F\n\
+        refs/remotes/origin/HEAD\x1E\x1E\x1E0020c2ab\x1Eone\x1E2024-01-02 03:04:05 +0000\x1E \x1Erefs/remotes/origin/main\x1F\n\
+        refs/remotes/origin/feature/x\x1E\x1E\x1E0020c2ab\x1Eone\x1E2024-01-02 03:04:05 +0000\x1E \x1E\x1F\n\
+        refs/remotes/origin/main\x1E\x1E\x1E0020c2ab\x1Eone\x1E2024-01-02 03:04:05 +0000\x1E \x1E\x1F\n\
+        refs/remotes/stray\x1E\x1E\x1E0020c2ab\x1Eone\x1E2024-01-02 03:04:05 +0000\x1E \x1E\x1F\n\
+        refs/remotes/team/up/feature/x\x1E\x1E\x1E0020c2ab\x1Eone\x1E2024-01-02 03:04:05 +0000\x1E \x1E\x1F\n";
+
+    #[test]
+    fn parses_branch_refs() {
+        let remotes = ["origin".to_string(), "team/up".to_string()];
+        let branches = parse_branch_refs(BRANCH_REFS, &remotes);
+
+        let names: Vec<(&str, &str, Option<&str>)> = branches
+            .iter()
+            .map(|b| (b.kind.as_str(), b.short_name.as_str(), b.remote.as_deref()))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("local", "feature/x", None),
+                ("local", "gone", None),
+                ("local", "main", None),
+                ("local", "origin/main", None),
+                ("remote", "old-remote/topic", Some("old-remote")),
+                ("remote", "origin/feature/x", Some("origin")),
+                ("remote", "origin/main", Some("origin")),
+                ("remote", "stray", None),
+                ("remote", "team/up/feature/x", Some("team/up")),
+            ]
+        );
+
+        // What the branch selector shows for remote branches.
+        let plain: Vec<&str> = branches
+            .iter()
+            .filter_map(|b| Some(&b.short_name[b.remote.as_ref()?.len() + 1..]))
+            .collect();
+        assert_eq!(plain, ["topic", "feature/x", "main", "feature/x"]);
+
+        let feature = &branches[0];
+        assert_eq!(feature.upstream.as_deref(), Some("origin/feature/x"));
+        assert_eq!((feature.ahead, feature.behind), (1, 0));
+        assert!(!feature.upstream_gone);
+
+        let gone = &branches[1];
+        assert!(gone.upstream_gone);
+        assert_eq!((gone.ahead, gone.behind), (0, 0));
+
+        let main = &branches[2];
+        assert!(main.is_current);
+        assert_eq!((main.ahead, main.behind), (2, 3));
+        assert_eq!(main.subject, "one");
+        assert!(branches.iter().filter(|b| b.is_current).count() == 1);
+        assert_eq!(branches[3].upstream, None);
+    }
+
+    #[tokio::test]
+    async fn lists_branches_with_tracking_state() {
+        let (repo, remote) = repo_with_remote();
+        git(repo.path(), &["push", "-q", "-u", "origin", "main"]);
+        git(repo.path(), &["branch", "gone"]);
+        git(repo.path(), &["push", "-q", "-u", "origin", "gone"]);
+        git(repo.path(), &["push", "-q", "origin", "--delete", "gone"]);
+        git(repo.path(), &["branch", "origin/main"]);
+        git(
+            repo.path(),
+            &["remote", "add", "team/up", &remote.path().to_string_lossy()],
+        );
+        git(repo.path(), &["fetch", "-q", "team/up"]);
+
+        commit_file(repo.path(), "a.txt", "ahead\n", "ahead");
+
+        let branches = get_git_branches(path_str(&repo)).await.unwrap();
+        let find = |kind: &str, name: &str| {
+            branches
+                .iter()
+                .find(|b| b.kind == kind && b.short_name == name)
+                .unwrap_or_else(|| panic!("missing {} {}", kind, name))
+        };
+
+        let main = find("local", "main");
+        assert!(main.is_current);
+        assert_eq!(main.upstream.as_deref(), Some("origin/main"));
+        assert_eq!((main.ahead, main.behind), (1, 0));
+
+        assert!(find("local", "gone").upstream_gone);
+        assert!(!find("local", "origin/main").is_current);
+        assert_eq!(
+            find("remote", "origin/main").remote.as_deref(),
+            Some("origin")
+        );
+        assert_eq!(
+            find("remote", "team/up/main").remote.as_deref(),
+            Some("team/up")
+        );
+    }
+
+    #[tokio::test]
+    async fn branch_names_are_never_parsed_as_options() {
+        let repo = init_repo();
+        commit_file(repo.path(), "a.txt", "one\n", "first");
+        git(repo.path(), &["branch", "keep"]);
+
+        assert!(
+            create_git_branch(path_str(&repo), "--help".into(), None, None)
+                .await
+                .is_err()
+        );
+        assert!(create_git_branch(
+            path_str(&repo),
+            "x".into(),
+            Some("--orphan".into()),
+            Some(true)
+        )
+        .await
+        .is_err());
+        assert!(
+            rename_git_branch(path_str(&repo), "-M".into(), "keep".into(), None)
+                .await
+                .is_err()
+        );
+        assert!(
+            delete_git_branch(path_str(&repo), "--all".into(), Some(true), None)
+                .await
+                .is_err()
+        );
+        assert!(set_branch_upstream(path_str(&repo), "--all".into(), None)
+            .await
+            .is_err());
+
+        assert_eq!(git(repo.path(), &["branch", "--show-current"]), "main");
+        assert_eq!(
+            git(repo.path(), &["branch", "--format=%(refname:short)"]),
+            "keep\nmain"
+        );
+    }
+
+    #[tokio::test]
+    async fn commit_details_never_parse_the_hash_as_an_option() {
+        let repo = init_repo();
+        let head = commit_file(repo.path(), "a.txt", "one\n", "first");
+
+        let output = repo.path().join("out.txt");
+        let option = format!("--output={}", output.display());
+        assert!(get_commit_details(path_str(&repo), option).await.is_err());
+        assert!(!output.exists());
+
+        let details = get_commit_details(path_str(&repo), head.clone())
+            .await
+            .unwrap();
+        assert_eq!(details.hash, head);
+        assert_eq!(details.files.len(), 1);
+    }
+
+    /// A repository with one commit on `main` and an empty bare `origin`.
+    fn repo_with_remote() -> (TempDir, TempDir) {
+        let remote = TempDir::new().unwrap();
+        git(remote.path(), &["init", "-q", "--bare", "-b", "main"]);
+
+        let repo = init_repo();
+        commit_file(repo.path(), "a.txt", "one\n", "first");
+        git(
+            repo.path(),
+            &["remote", "add", "origin", &remote.path().to_string_lossy()],
+        );
+
+        (repo, remote)
+    }
+
+    #[tokio::test]
+    async fn deletes_the_remote_upstream_with_the_branch() {
+        let (repo, remote) = repo_with_remote();
+        git(repo.path(), &["push", "-q", "origin", "main"]);
+        git(repo.path(), &["switch", "-q", "-c", "topic"]);
+        git(
+            repo.path(),
+            &["push", "-q", "-u", "origin", "topic:remote-topic"],
+        );
+        git(repo.path(), &["switch", "-q", "main"]);
+
+        delete_git_branch(path_str(&repo), "topic".into(), Some(true), Some(true))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            git(remote.path(), &["for-each-ref", "--format=%(refname)"]),
+            "refs/heads/main"
+        );
+    }
+
+    #[tokio::test]
+    async fn never_deletes_a_remote_branch_without_a_remote_upstream() {
+        let (repo, remote) = repo_with_remote();
+        git(repo.path(), &["push", "-q", "origin", "main"]);
+        git(repo.path(), &["branch", "--track", "local-only", "main"]);
+        git(repo.path(), &["branch", "untracked"]);
+
+        for branch in ["local-only", "untracked"] {
+            let result =
+                delete_git_branch(path_str(&repo), branch.into(), Some(true), Some(true)).await;
+            assert!(matches!(result, Err(e) if e.contains("no remote upstream")));
+        }
+
+        assert_eq!(
+            git(repo.path(), &["branch", "--format=%(refname:short)"]),
+            "local-only\nmain\nuntracked"
+        );
+        assert_eq!(
+            git(remote.path(), &["for-each-ref", "--format=%(refname)"]),
+            "refs/heads/main"
+        );
+    }
+
+    #[test]
+    fn parses_fetch_output_lines() {
+        let stderr = "From /tmp/fr\n\
+             \x20- [deleted]         (none)     -> origin/old\n\
+             \x20  931f958..35fdd18  main       -> origin/main\n\
+             \x20+ 931f958...91628ac forced     -> origin/forced  (forced update)\n\
+             \x20* [new branch]      newb       -> origin/newb\n\
+             \x20* [new tag]         v1         -> v1\n\
+             From /tmp/other\n\
+             \x20! [rejected]        v2         -> v2  (would clobber existing tag)\n\
+             \x20t [tag update]      v3         -> v3\n\
+             Fetching upstream\n";
+
+        let updates = parse_fetch_output(stderr);
+        let summary: Vec<(&str, &str, &str, &str)> = updates
+            .iter()
+            .map(|u| {
+                (
+                    u.kind.as_str(),
+                    u.source.as_str(),
+                    u.from_ref.as_str(),
+                    u.to_ref.as_str(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            [
+                ("deleted", "/tmp/fr", "(none)", "origin/old"),
+                ("updated", "/tmp/fr", "main", "origin/main"),
+                ("forced", "/tmp/fr", "forced", "origin/forced"),
+                ("created", "/tmp/fr", "newb", "origin/newb"),
+                ("created", "/tmp/fr", "v1", "v1"),
+                ("rejected", "/tmp/other", "v2", "v2"),
+                ("tag-updated", "/tmp/other", "v3", "v3"),
+            ]
+        );
+
+        assert_eq!(updates[1].old_oid.as_deref(), Some("931f958"));
+        assert_eq!(updates[1].new_oid.as_deref(), Some("35fdd18"));
+        assert_eq!(updates[1].reason, None);
+        assert_eq!(updates[2].old_oid.as_deref(), Some("931f958"));
+        assert_eq!(updates[2].new_oid.as_deref(), Some("91628ac"));
+        assert_eq!(updates[2].reason.as_deref(), Some("forced update"));
+        assert_eq!(updates[3].old_oid, None);
+        assert_eq!(
+            updates[5].reason.as_deref(),
+            Some("would clobber existing tag")
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_reports_pruned_branches_and_new_tags() {
+        let (repo, remote) = repo_with_remote();
+        git(repo.path(), &["branch", "old"]);
+        git(repo.path(), &["push", "-q", "origin", "main", "old"]);
+        git(repo.path(), &["fetch", "-q", "origin"]);
+
+        let other = TempDir::new().unwrap();
+        git(
+            other.path(),
+            &[
+                "clone",
+                "-q",
+                &remote.path().to_string_lossy(),
+                &other.path().to_string_lossy(),
+            ],
+        );
+        git(other.path(), &["config", "user.name", "Other"]);
+        git(other.path(), &["config", "user.email", "other@example.com"]);
+        let head = commit_file(other.path(), "b.txt", "theirs\n", "theirs");
+        git(other.path(), &["tag", "v1"]);
+        git(
+            other.path(),
+            &["push", "-q", "origin", "main", "v1", ":old"],
+        );
+
+        let result = fetch_git_remote(path_str(&repo), None, Some(false), Some(false))
+            .await
+            .unwrap();
+        assert_eq!(result.remotes, ["origin"]);
+        assert!(result.updates.iter().all(|u| u.kind != "deleted"));
+        assert!(result
+            .updates
+            .iter()
+            .any(|u| u.kind == "updated" && u.to_ref == "origin/main"));
+        assert_eq!(git(repo.path(), &["rev-parse", "origin/main"]), head);
+
+        let result = fetch_git_remote(
+            path_str(&repo),
+            Some("origin".into()),
+            Some(true),
+            Some(true),
+        )
+        .await
+        .unwrap();
+        assert!(result
+            .updates
+            .iter()
+            .any(|u| u.kind == "deleted" && u.to_ref == "origin/old"));
+        assert!(git(repo.path(), &["branch", "-r"])
+            .lines()
+            .all(|b| !b.contains("old")));
+        assert_eq!(git(repo.path(), &["tag", "--list"]), "v1");
+
+        let missing = fetch_git_remote(path_str(&repo), Some("nope".into()), None, None).await;
+        assert!(matches!(missing, Err(e) if e.contains("No such remote")));
+    }
+
+    #[test]
+    fn parses_push_porcelain_lines() {
+        let stdout = "To /tmp/remote.git\n\
+             *\trefs/heads/feature:refs/heads/feature\t[new branch]\n\
+             +\trefs/heads/main:refs/heads/main\t1a2b3c4...5d6e7f8 (forced update)\n\
+             !\trefs/heads/dev:refs/heads/dev\t[rejected] (non-fast-forward)\n\
+             -\t:refs/heads/old\t[deleted]\n\
+             =\trefs/tags/v1:refs/tags/v1\t[up to date]\n\
+             Done\n";
+
+        let updates = parse_push_porcelain(stdout);
+        let statuses: Vec<&str> = updates.iter().map(|u| u.status.as_str()).collect();
+        assert_eq!(
+            statuses,
+            ["new", "forced", "rejected", "deleted", "up-to-date"]
+        );
+
+        assert_eq!(updates[0].local_ref, "refs/heads/feature");
+        assert_eq!(updates[0].summary, "[new branch]");
+        assert_eq!(updates[0].reason, None);
+
+        assert_eq!(updates[1].summary, "1a2b3c4...5d6e7f8");
+        assert_eq!(updates[1].reason.as_deref(), Some("forced update"));
+
+        assert_eq!(updates[2].remote_ref, "refs/heads/dev");
+        assert_eq!(updates[2].reason.as_deref(), Some("non-fast-forward"));
+
+        assert_eq!(updates[3].local_ref, "");
+        assert_eq!(updates[3].remote_ref, "refs/heads/old");
+    }
+
+    #[test]
+    fn ignores_non_ref_lines() {
+        assert!(parse_push_porcelain("To origin\nDone\n\n").is_empty());
+    }
+
+    #[tokio::test]
+    async fn first_push_sets_upstream() {
+        let (repo, remote) = repo_with_remote();
+
+        let result = push_git_branch(
+            path_str(&repo),
+            "main".into(),
+            Some("origin".into()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert!(result.success);
+        assert!(result.upstream_set);
+        assert!(!result.rejected);
+        assert_eq!(result.remote, "origin");
+        assert_eq!(result.updates.len(), 1);
+        assert_eq!(result.updates[0].status, "new");
+        assert_eq!(
+            git(repo.path(), &["config", "branch.main.remote"]),
+            "origin"
+        );
+        assert_eq!(
+            git(remote.path(), &["rev-parse", "main"]),
+            git(repo.path(), &["rev-parse", "HEAD"])
+        );
+
+        // The second push already has an upstream.
+        commit_file(repo.path(), "a.txt", "two\n", "second");
+        let result = push_git_branch(path_str(&repo), "main".into(), None, None, None)
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(!result.upstream_set);
+        assert_eq!(result.updates[0].status, "fast-forward");
+    }
+
+    #[tokio::test]
+    async fn force_with_lease_replaces_rewritten_history() {
+        let (repo, remote) = repo_with_remote();
+        push_git_branch(
+            path_str(&repo),
+            "main".into(),
+            Some("origin".into()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        git(repo.path(), &["commit", "-q", "--amend", "-m", "rewritten"]);
+
+        let result = push_git_branch(path_str(&repo), "main".into(), None, Some(true), None)
+            .await
+            .unwrap();
+
+        assert!(result.success);
+        assert!(!result.rejected);
+        assert_eq!(result.updates[0].status, "forced");
+        assert_eq!(
+            git(remote.path(), &["log", "-1", "--format=%s", "main"]),
+            "rewritten"
+        );
+    }
+
+    #[tokio::test]
+    async fn non_fast_forward_push_is_reported_as_rejection() {
+        let (repo, remote) = repo_with_remote();
+        push_git_branch(
+            path_str(&repo),
+            "main".into(),
+            Some("origin".into()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        // Someone else pushes first.
+        let other = TempDir::new().unwrap();
+        git(
+            other.path(),
+            &[
+                "clone",
+                "-q",
+                &remote.path().to_string_lossy(),
+                &other.path().to_string_lossy(),
+            ],
+        );
+        git(other.path(), &["config", "user.name", "Other"]);
+        git(other.path(), &["config", "user.email", "other@example.com"]);
+        commit_file(other.path(), "b.txt", "theirs\n", "theirs");
+        git(other.path(), &["push", "-q", "origin", "main"]);
+
+        commit_file(repo.path(), "c.txt", "ours\n", "ours");
+        let result = push_git_branch(path_str(&repo), "main".into(), None, None, None)
+            .await
+            .unwrap();
+
+        assert!(!result.success);
+        assert!(result.rejected);
+        assert!(result.non_fast_forward);
+        assert!(!result.upstream_set);
+        assert_eq!(result.updates.len(), 1);
+        assert_eq!(result.updates[0].status, "rejected");
+        assert_eq!(result.updates[0].remote_ref, "refs/heads/main");
+        assert_eq!(result.updates[0].reason.as_deref(), Some("fetch first"));
+    }
+
+    #[tokio::test]
+    async fn push_to_missing_remote_is_an_error() {
+        let repo = init_repo();
+        commit_file(repo.path(), "a.txt", "one\n", "first");
+
+        let result = push_git_branch(
+            path_str(&repo),
+            "main".into(),
+            Some("origin".into()),
+            None,
+            None,
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn push_without_upstream_or_remote_is_an_error() {
+        let (repo, remote) = repo_with_remote();
+
+        let result = push_git_branch(path_str(&repo), "main".into(), None, None, None).await;
+        assert!(matches!(result, Err(e) if e.contains("no upstream")));
+        assert!(git(remote.path(), &["for-each-ref"]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn push_remote_is_never_parsed_as_an_option() {
+        let (repo, remote) = repo_with_remote();
+        push_git_branch(
+            path_str(&repo),
+            "main".into(),
+            Some("origin".into()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        git(repo.path(), &["branch", "keep"]);
+
+        for option in ["--mirror", "--delete", "--all"] {
+            let result = push_git_branch(
+                path_str(&repo),
+                "main".into(),
+                Some(option.into()),
+                None,
+                None,
+            )
+            .await;
+            assert!(result.is_err(), "{}", option);
+        }
+
+        assert_eq!(
+            git(remote.path(), &["for-each-ref", "--format=%(refname)"]),
+            "refs/heads/main"
+        );
+    }
+
+    async fn query(repo: &TempDir, filter: CommitFilter) -> Vec<String> {
+        query_git_commits(path_str(repo), filter, None, None)
+            .await
+            .unwrap_or_else(|e| panic!("query failed: {}", e))
+            .into_iter()
+            .map(|commit| commit.message)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn filters_commits_by_author() {
+        let repo = init_repo();
+        commit_file(repo.path(), "a.txt", "1\n", "by test");
+        write_file(repo.path(), "a.txt", "2\n");
+        git(
+            repo.path(),
+            &[
+                "-c",
+                "user.name=Other Person",
+                "-c",
+                "user.email=other@example.com",
+                "commit",
+                "-q",
+                "-am",
+                "by other",
+            ],
+        );
+
+        let filter = |author: &str, ignore_case| CommitFilter {
+            author: Some(author.to_string()),
+            ignore_case,
+            ..Default::default()
+        };
+        assert_eq!(query(&repo, filter("Other", false)).await, ["by other"]);
+        assert_eq!(
+            query(&repo, filter("^test@", false)).await,
+            Vec::<String>::new()
+        );
+        assert_eq!(query(&repo, filter("TEST", true)).await, ["by test"]);
+    }
+
+    #[tokio::test]
+    async fn filters_commits_by_path_and_follows_renames() {
+        let repo = init_repo();
+        commit_file(repo.path(), "old.txt", "one\ntwo\nthree\n", "add old");
+        commit_file(repo.path(), "other.txt", "x\n", "add other");
+        git(repo.path(), &["mv", "old.txt", "new.txt"]);
+        git(repo.path(), &["commit", "-q", "-m", "rename"]);
+        commit_file(
+            repo.path(),
+            "new.txt",
+            "one\ntwo\nthree\nfour\n",
+            "edit new",
+        );
+
+        let by_path = |follow| CommitFilter {
+            paths: vec![repo.path().join("new.txt").to_string_lossy().to_string()],
+            follow,
+            ..Default::default()
+        };
+        assert_eq!(query(&repo, by_path(false)).await, ["edit new", "rename"]);
+        assert_eq!(
+            query(&repo, by_path(true)).await,
+            ["edit new", "rename", "add old"]
+        );
+
+        let two_paths = CommitFilter {
+            paths: vec!["new.txt".to_string(), "other.txt".to_string()],
+            follow: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            query_git_commits(path_str(&repo), two_paths, None, None).await,
+            Err(e) if e == "Following renames requires exactly one file path"
+        ));
+    }
+
+    #[tokio::test]
+    async fn lists_only_merges_across_all_branches() {
+        let repo = init_repo();
+        commit_file(repo.path(), "a.txt", "1\n", "base");
+        git(repo.path(), &["switch", "-q", "-c", "topic"]);
+        commit_file(repo.path(), "b.txt", "1\n", "topic work");
+        git(repo.path(), &["switch", "-q", "-c", "side", "main"]);
+        commit_file(repo.path(), "c.txt", "1\n", "side work");
+        git(
+            repo.path(),
+            &["merge", "-q", "--no-ff", "-m", "merge topic", "topic"],
+        );
+        git(repo.path(), &["switch", "-q", "main"]);
+        write_file(repo.path(), "a.txt", "stashed\n");
+        git(repo.path(), &["stash", "-q"]);
+        let snapshot = git(
+            repo.path(),
+            &["commit-tree", "-p", "HEAD", "-m", "snapshot", "HEAD^{tree}"],
+        );
+        git(
+            repo.path(),
+            &["update-ref", "refs/git-client/snapshots/1", &snapshot],
+        );
+
+        assert_eq!(query(&repo, CommitFilter::default()).await, ["base"]);
+
+        let all = CommitFilter {
+            all: true,
+            ..Default::default()
+        };
+        let mut messages = query(&repo, all).await;
+        messages.sort();
+        assert_eq!(messages, ["base", "merge topic", "side work", "topic work"]);
+
+        let merges = CommitFilter {
+            all: true,
+            merges_only: true,
+            ..Default::default()
+        };
+        assert_eq!(query(&repo, merges).await, ["merge topic"]);
+
+        let conflicting = CommitFilter {
+            merges_only: true,
+            no_merges: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            query_git_commits(path_str(&repo), conflicting, None, None).await,
+            Err(e) if e == "Merges only cannot be combined with no merges"
+        ));
+    }
+}
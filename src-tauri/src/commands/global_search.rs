@@ -1,14 +1,7 @@
 use std::path::Path;
 use tokio::process::Command;
 
-#[derive(serde::Serialize)]
-pub struct Commit {
-    pub hash: String,
-    pub author: String,
-    pub email: String,
-    pub date: String,
-    pub message: String,
-}
+use super::git::{parse_commit_log, Commit, COMMIT_LOG_FORMAT};
 
 #[derive(serde::Serialize)]
 pub struct GlobalSearchResult {
@@ -78,7 +71,8 @@ async fn search_commits(repo: &Path, query: &str) -> Result<Vec<Commit>, String>
         .arg("log")
         .arg("--grep")
         .arg(query)
-        .arg("--pretty=format:%H%x1E%an%x1E%ae%x1E%ai%x1E%s%x1F")
+        .arg("--decorate=full")
+        .arg(COMMIT_LOG_FORMAT)
         .arg("-i")
         .arg("-n")
         .arg("50")
@@ -97,35 +91,5 @@ async fn search_commits(repo: &Path, query: &str) -> Result<Vec<Commit>, String>
         return Ok(Vec::new());
     }
 
-    let mut commits = Vec::new();
-    let record_separator = '\u{001E}';
-    let unit_separator = '\u{001F}';
-
-    for record in stdout.split(unit_separator) {
-        if record.trim().is_empty() {
-            continue;
-        }
-
-        let parts: Vec<&str> = record.split(record_separator).collect();
-        if parts.len() >= 5 {
-            let hash = parts[0].trim().to_string();
-            let author = parts[1].trim().to_string();
-            let email = parts[2].trim().to_string();
-            let date_str = parts[3].trim().to_string();
-            let message = parts[4..]
-                .join(&record_separator.to_string())
-                .trim()
-                .to_string();
-
-            commits.push(Commit {
-                hash,
-                author,
-                email,
-                date: date_str,
-                message,
-            });
-        }
-    }
-
-    Ok(commits)
+    Ok(parse_commit_log(&stdout))
 }
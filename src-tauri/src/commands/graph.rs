@@ -0,0 +1,358 @@
+use std::path::Path;
+use tokio::process::Command;
+
+use super::git::{parse_commit_log, Commit, ALL_REFS_EXCLUDES, COMMIT_LOG_FORMAT};
+
+/// A line between two rows of the graph: from `from_lane` on the row above
+/// to `to_lane` on this row.
+#[derive(serde::Serialize)]
+pub struct GraphEdge {
+    pub from_lane: usize,
+    pub to_lane: usize,
+    pub color: usize,
+}
+
+#[derive(serde::Serialize)]
+pub struct GraphRow {
+    pub commit: Commit,
+    pub lane: usize,
+    pub color: usize,
+    pub edges: Vec<GraphEdge>,
+    pub width: usize,
+}
+
+/// A column waiting for `oid`, fed by lines from `sources` on the row above.
+struct Lane {
+    oid: String,
+    sources: Vec<usize>,
+    color: usize,
+}
+
+/// Assigns each commit a lane and computes the edges connecting it to the
+/// row above. Commits must be in topological order, children first. A
+/// commit's first parent continues in its lane so branches stay straight;
+/// further parents join the lane already waiting for them or open a new one.
+pub(crate) fn layout_graph(commits: Vec<Commit>) -> Vec<GraphRow> {
+    let mut lanes: Vec<Option<Lane>> = Vec::new();
+    let mut next_color = 0;
+    let mut rows = Vec::with_capacity(commits.len());
+
+    for commit in commits {
+        let lane = lanes
+            .iter()
+            .position(|l| l.as_ref().is_some_and(|l| l.oid == commit.hash))
+            .or_else(|| lanes.iter().position(|l| l.is_none()))
+            .unwrap_or(lanes.len());
+
+        if lane == lanes.len() {
+            lanes.push(None);
+        }
+
+        let color = match &lanes[lane] {
+            Some(existing) if existing.oid == commit.hash => existing.color,
+            _ => {
+                next_color += 1;
+                next_color - 1
+            }
+        };
+
+        let mut edges = Vec::new();
+
+        for (index, slot) in lanes.iter_mut().enumerate() {
+            let Some(current) = slot else {
+                continue;
+            };
+
+            let to_lane = if current.oid == commit.hash {
+                lane
+            } else {
+                index
+            };
+
+            for &from_lane in &current.sources {
+                edges.push(GraphEdge {
+                    from_lane,
+                    to_lane,
+                    color: current.color,
+                });
+            }
+
+            if current.oid == commit.hash {
+                *slot = None;
+            } else {
+                current.sources = vec![index];
+            }
+        }
+
+        if let Some(first) = commit.parents.first() {
+            lanes[lane] = Some(Lane {
+                oid: first.clone(),
+                sources: vec![lane],
+                color,
+            });
+        }
+
+        for parent in commit.parents.iter().skip(1) {
+            if let Some(existing) = lanes.iter_mut().flatten().find(|l| &l.oid == parent) {
+                existing.sources.push(lane);
+                continue;
+            }
+
+            let slot = lanes
+                .iter()
+                .position(|l| l.is_none())
+                .unwrap_or(lanes.len());
+            if slot == lanes.len() {
+                lanes.push(None);
+            }
+
+            lanes[slot] = Some(Lane {
+                oid: parent.clone(),
+                sources: vec![lane],
+                color: next_color,
+            });
+            next_color += 1;
+        }
+
+        while lanes.last().is_some_and(|l| l.is_none()) {
+            lanes.pop();
+        }
+
+        let width = lanes.len().max(lane + 1);
+
+        rows.push(GraphRow {
+            commit,
+            lane,
+            color,
+            edges,
+            width,
+        });
+    }
+
+    rows
+}
+
+/// Returns `--all` history in topological order with its graph layout. The
+/// layout is computed from the first commit so pages stay consistent.
+#[tauri::command]
+pub async fn get_commit_graph(
+    repo_path: String,
+    skip: Option<u32>,
+    limit: Option<u32>,
+) -> Result<Vec<GraphRow>, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.arg("--no-pager")
+        .arg("log")
+        .args(ALL_REFS_EXCLUDES)
+        .arg("--all")
+        .arg("--topo-order")
+        .arg("--decorate=full")
+        .arg(COMMIT_LOG_FORMAT);
+
+    if let Some(limit_val) = limit {
+        let total_needed = skip.unwrap_or(0) + limit_val;
+        cmd.arg("-n").arg(total_needed.to_string());
+    }
+
+    let output = cmd
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git log: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git log command failed: {}", stderr));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let rows = layout_graph(parse_commit_log(&stdout))
+        .into_iter()
+        .skip(skip.unwrap_or(0) as usize)
+        .collect();
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::reset::create_snapshot;
+    use crate::commands::test_util::{commit_file, git, init_repo, path_str, write_file};
+
+    fn commit(hash: &str, parents: &[&str]) -> Commit {
+        Commit {
+            hash: hash.to_string(),
+            parents: parents.iter().map(|p| p.to_string()).collect(),
+            refs: Vec::new(),
+            author: String::new(),
+            email: String::new(),
+            date: String::new(),
+            message: String::new(),
+            body: String::new(),
+            trailers: Vec::new(),
+            co_authors: Vec::new(),
+        }
+    }
+
+    /// `(lane, width, edges)` per row, edges as `(from, to, color)`.
+    type Layout = Vec<(usize, usize, Vec<(usize, usize, usize)>)>;
+
+    fn layout(commits: Vec<Commit>) -> Layout {
+        layout_graph(commits)
+            .into_iter()
+            .map(|row| {
+                let edges = row
+                    .edges
+                    .iter()
+                    .map(|e| (e.from_lane, e.to_lane, e.color))
+                    .collect();
+                (row.lane, row.width, edges)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn keeps_linear_history_in_one_lane() {
+        let rows = layout(vec![
+            commit("c", &["b"]),
+            commit("b", &["a"]),
+            commit("a", &[]),
+        ]);
+
+        assert_eq!(
+            rows,
+            vec![
+                (0, 1, vec![]),
+                (0, 1, vec![(0, 0, 0)]),
+                (0, 1, vec![(0, 0, 0)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn opens_and_closes_a_lane_for_a_merge() {
+        let rows = layout(vec![
+            commit("m", &["b", "c"]),
+            commit("c", &["a"]),
+            commit("b", &["a"]),
+            commit("a", &[]),
+        ]);
+
+        assert_eq!(
+            rows,
+            vec![
+                (0, 2, vec![]),
+                (1, 2, vec![(0, 0, 0), (0, 1, 1)]),
+                (0, 2, vec![(0, 0, 0), (1, 1, 1)]),
+                (0, 1, vec![(0, 0, 0), (1, 0, 1)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn gives_each_octopus_parent_its_own_lane() {
+        let rows = layout(vec![
+            commit("o", &["p1", "p2", "p3"]),
+            commit("p1", &["b"]),
+            commit("p2", &["b"]),
+            commit("p3", &["b"]),
+            commit("b", &[]),
+        ]);
+
+        assert_eq!(
+            rows,
+            vec![
+                (0, 3, vec![]),
+                (0, 3, vec![(0, 0, 0), (0, 1, 1), (0, 2, 2)]),
+                (1, 3, vec![(0, 0, 0), (1, 1, 1), (2, 2, 2)]),
+                (2, 3, vec![(0, 0, 0), (1, 1, 1), (2, 2, 2)]),
+                (0, 1, vec![(0, 0, 0), (1, 0, 1), (2, 0, 2)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn joins_lanes_waiting_for_the_same_commit_in_a_criss_cross() {
+        let rows = layout(vec![
+            commit("m2", &["c1", "b1"]),
+            commit("m1", &["b1", "c1"]),
+            commit("b1", &["a"]),
+            commit("c1", &["a"]),
+            commit("a", &[]),
+        ]);
+
+        // m1's first parent opens a second lane for b1; both end at b1, and
+        // m1's second parent joins the lane m2 already opened for c1.
+        assert_eq!(
+            rows,
+            vec![
+                (0, 2, vec![]),
+                (2, 3, vec![(0, 0, 0), (0, 1, 1)]),
+                (1, 2, vec![(0, 0, 0), (2, 0, 0), (1, 1, 1), (2, 1, 2)]),
+                (0, 2, vec![(0, 0, 0), (1, 1, 1)]),
+                (0, 1, vec![(0, 0, 0), (1, 0, 1)]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn lays_out_an_octopus_merge_from_git_log() {
+        let repo = init_repo();
+        let base = commit_file(repo.path(), "base.txt", "base\n", "base");
+        for name in ["one", "two"] {
+            git(repo.path(), &["switch", "-q", "-c", name, "main"]);
+            commit_file(repo.path(), &format!("{}.txt", name), "x\n", name);
+        }
+        git(repo.path(), &["switch", "-q", "main"]);
+        commit_file(repo.path(), "main.txt", "x\n", "main");
+        git(repo.path(), &["merge", "-q", "--no-edit", "one", "two"]);
+
+        let rows = get_commit_graph(path_str(&repo), None, None).await.unwrap();
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[0].commit.parents.len(), 3);
+        assert_eq!(rows[0].width, 3);
+        assert_eq!(rows[4].commit.hash, base);
+        assert_eq!(rows[4].lane, 0);
+        assert_eq!(rows[4].width, 1);
+        assert_eq!(rows[4].edges.len(), 3);
+        assert!(rows[4].edges.iter().all(|e| e.to_lane == 0));
+
+        let page = get_commit_graph(path_str(&repo), Some(2), Some(2))
+            .await
+            .unwrap();
+        assert_eq!(page.len(), 2);
+        assert_eq!(page[0].commit.hash, rows[2].commit.hash);
+        assert_eq!(page[0].lane, rows[2].lane);
+    }
+
+    #[tokio::test]
+    async fn leaves_out_snapshots_and_the_stash() {
+        let repo = init_repo();
+        commit_file(repo.path(), "a.txt", "1\n", "first");
+        let head = commit_file(repo.path(), "a.txt", "2\n", "second");
+        write_file(repo.path(), "a.txt", "stashed\n");
+        git(repo.path(), &["stash", "-q"]);
+        write_file(repo.path(), "a.txt", "snapshot\n");
+        let snapshot = create_snapshot(repo.path(), "test").await.unwrap();
+
+        let rows = get_commit_graph(path_str(&repo), None, None).await.unwrap();
+
+        let hashes: Vec<&str> = rows.iter().map(|row| row.commit.hash.as_str()).collect();
+        assert_eq!(hashes.len(), 2);
+        assert_eq!(hashes[0], head);
+        assert!(!hashes.contains(&snapshot.hash.as_str()));
+    }
+}
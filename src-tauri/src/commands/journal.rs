@@ -0,0 +1,611 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use super::git::run_git;
+use super::merge::head_oid;
+use super::reset::create_snapshot;
+
+/// How many operations are kept in the journal.
+const MAX_ENTRIES: usize = 100;
+
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Upstream {
+    pub remote: String,
+    pub merge: String,
+}
+
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RepoState {
+    pub head_ref: Option<String>,
+    pub head: Option<String>,
+    pub branches: BTreeMap<String, String>,
+    #[serde(default)]
+    pub upstreams: BTreeMap<String, Upstream>,
+    #[serde(default)]
+    pub stashes: Vec<String>,
+    pub index_tree: Option<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct JournalEntry {
+    pub id: String,
+    pub timestamp: i64,
+    pub operation: String,
+    pub worktree: bool,
+    pub undone: bool,
+    pub before: RepoState,
+    pub after: RepoState,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PendingOperation {
+    operation: String,
+    before: RepoState,
+}
+
+fn journal_dir(repo: &Path) -> PathBuf {
+    repo.join(".git").join("git-client").join("journal")
+}
+
+fn pending_file(repo: &Path) -> PathBuf {
+    journal_dir(repo).join("pending.json")
+}
+
+/// Captures HEAD, the local branch refs with their upstreams, the stash list
+/// (newest first) and the index as a tree. The index tree is `None` while
+/// there are unmerged entries.
+pub(crate) async fn capture_state(repo: &Path) -> Option<RepoState> {
+    let head_ref = run_git(repo, &["symbolic-ref", "--quiet", "HEAD"])
+        .await
+        .ok();
+    let head = head_oid(repo).await;
+
+    let refs = run_git(
+        repo,
+        &[
+            "for-each-ref",
+            "--format=%(refname)%00%(objectname)%00%(upstream:remotename)%00%(upstream:remoteref)",
+            "refs/heads",
+        ],
+    )
+    .await
+    .ok()?;
+
+    let mut branches = BTreeMap::new();
+    let mut upstreams = BTreeMap::new();
+
+    for line in refs.lines() {
+        let parts: Vec<&str> = line.split('\0').collect();
+        if parts.len() < 4 {
+            continue;
+        }
+
+        branches.insert(parts[0].to_string(), parts[1].to_string());
+        if !parts[2].is_empty() && !parts[3].is_empty() {
+            upstreams.insert(
+                parts[0].to_string(),
+                Upstream {
+                    remote: parts[2].to_string(),
+                    merge: parts[3].to_string(),
+                },
+            );
+        }
+    }
+
+    // Fails when there is no stash yet.
+    let stashes = run_git(repo, &["log", "-g", "--format=%H", "refs/stash", "--"])
+        .await
+        .map(|stdout| stdout.lines().map(str::to_string).collect())
+        .unwrap_or_default();
+
+    let index_tree = run_git(repo, &["write-tree"]).await.ok();
+
+    Some(RepoState {
+        head_ref,
+        head,
+        branches,
+        upstreams,
+        stashes,
+        index_tree,
+    })
+}
+
+fn read_entries(repo: &Path) -> Vec<JournalEntry> {
+    let Ok(entries) = std::fs::read_dir(journal_dir(repo)) else {
+        return Vec::new();
+    };
+
+    let mut journal = Vec::new();
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path == pending_file(repo) || path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+
+        if let Ok(record) = serde_json::from_str::<JournalEntry>(&content) {
+            journal.push(record);
+        }
+    }
+
+    journal.sort_by(|a, b| a.id.cmp(&b.id));
+    journal
+}
+
+fn write_entry(repo: &Path, entry: &JournalEntry) -> Result<(), String> {
+    let dir = journal_dir(repo);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create journal directory: {}", e))?;
+    let content = serde_json::to_string_pretty(entry)
+        .map_err(|e| format!("Failed to serialize journal entry: {}", e))?;
+    std::fs::write(dir.join(format!("{}.json", entry.id)), content)
+        .map_err(|e| format!("Failed to write journal entry: {}", e))
+}
+
+fn remove_entry(repo: &Path, entry: &JournalEntry) -> Result<(), String> {
+    let path = journal_dir(repo).join(format!("{}.json", entry.id));
+    std::fs::remove_file(&path).map_err(|e| format!("Failed to remove journal entry: {}", e))
+}
+
+/// Writes `entry` after dropping undone entries, which can no longer be
+/// redone on top of the new state, and the oldest ones over `MAX_ENTRIES`.
+fn append_entry(repo: &Path, entry: &JournalEntry) -> Result<(), String> {
+    let existing = read_entries(repo);
+    for stale in existing.iter().filter(|stale| stale.undone) {
+        remove_entry(repo, stale)?;
+    }
+
+    let kept: Vec<&JournalEntry> = existing.iter().filter(|kept| !kept.undone).collect();
+    for old in kept
+        .iter()
+        .take((kept.len() + 1).saturating_sub(MAX_ENTRIES))
+    {
+        remove_entry(repo, old)?;
+    }
+
+    write_entry(repo, entry)
+}
+
+/// Appends an entry for `operation` if it changed anything. The operation
+/// has already happened, so a journal failure does not turn it into an error.
+pub(crate) async fn record_operation(
+    repo: &Path,
+    operation: &str,
+    worktree: bool,
+    before: Option<RepoState>,
+) {
+    let Some(before) = before else {
+        return;
+    };
+    let Some(after) = capture_state(repo).await else {
+        return;
+    };
+
+    if before == after {
+        return;
+    }
+
+    let Ok(now) = SystemTime::now().duration_since(UNIX_EPOCH) else {
+        return;
+    };
+
+    let entry = JournalEntry {
+        id: format!("{}-{:09}", now.as_secs(), now.subsec_nanos()),
+        timestamp: now.as_secs() as i64,
+        operation: operation.to_string(),
+        worktree,
+        undone: false,
+        before,
+        after,
+    };
+
+    let _ = append_entry(repo, &entry);
+}
+
+/// Remembers the state before an operation that may stop for conflicts, so
+/// it is journaled as a whole once it is continued to completion.
+pub(crate) async fn begin_pending(repo: &Path, operation: &str) {
+    let Some(before) = capture_state(repo).await else {
+        return;
+    };
+
+    let pending = PendingOperation {
+        operation: operation.to_string(),
+        before,
+    };
+
+    // Without the file the operation is journaled step by step instead.
+    let Ok(content) = serde_json::to_string(&pending) else {
+        return;
+    };
+    let _ = std::fs::create_dir_all(journal_dir(repo))
+        .and_then(|_| std::fs::write(pending_file(repo), content));
+}
+
+fn take_pending(repo: &Path, operation: &str) -> Option<RepoState> {
+    let path = pending_file(repo);
+    let content = std::fs::read_to_string(&path).ok()?;
+    let pending: PendingOperation = serde_json::from_str(&content).ok()?;
+
+    if pending.operation != operation {
+        return None;
+    }
+
+    let _ = std::fs::remove_file(&path);
+    Some(pending.before)
+}
+
+/// Journals a pending operation that has completed. Returns false when no
+/// such operation was pending.
+pub(crate) async fn finish_pending(repo: &Path, operation: &str) -> bool {
+    match take_pending(repo, operation) {
+        Some(before) => {
+            record_operation(repo, operation, true, Some(before)).await;
+            true
+        }
+        None => false,
+    }
+}
+
+pub(crate) fn discard_pending(repo: &Path, operation: &str) {
+    take_pending(repo, operation);
+}
+
+async fn update_refs(
+    repo: &Path,
+    from: &RepoState,
+    to: &RepoState,
+    reason: &str,
+) -> Result<(), String> {
+    let mut transaction = String::new();
+
+    for (name, oid) in &to.branches {
+        if from.branches.get(name) != Some(oid) {
+            transaction.push_str(&format!("update {} {}\n", name, oid));
+        }
+    }
+
+    for name in from.branches.keys() {
+        if !to.branches.contains_key(name) {
+            transaction.push_str(&format!("delete {}\n", name));
+        }
+    }
+
+    if transaction.is_empty() {
+        return Ok(());
+    }
+
+    let mut child = Command::new("git")
+        .arg("update-ref")
+        .arg("-m")
+        .arg(reason)
+        .arg("--stdin")
+        .current_dir(repo)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to execute git update-ref: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(transaction.as_bytes())
+            .await
+            .map_err(|e| format!("Failed to write ref updates: {}", e))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|e| format!("Failed to execute git update-ref: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git update-ref failed: {}", stderr));
+    }
+
+    Ok(())
+}
+
+/// Points each branch whose upstream differs between the states at its
+/// upstream in `to`, or removes it.
+async fn update_upstreams(repo: &Path, from: &RepoState, to: &RepoState) -> Result<(), String> {
+    let names: BTreeSet<&String> = from.upstreams.keys().chain(to.upstreams.keys()).collect();
+
+    for name in names {
+        let target = to.upstreams.get(name);
+        if from.upstreams.get(name) == target {
+            continue;
+        }
+
+        let branch = name.strip_prefix("refs/heads/").unwrap_or(name);
+        let remote_key = format!("branch.{}.remote", branch);
+        let merge_key = format!("branch.{}.merge", branch);
+
+        match target {
+            Some(upstream) => {
+                run_git(repo, &["config", &remote_key, &upstream.remote]).await?;
+                run_git(repo, &["config", &merge_key, &upstream.merge]).await?;
+            }
+            None => {
+                // Deleting a branch may already have removed its section.
+                let _ = run_git(repo, &["config", "--unset", &remote_key]).await;
+                let _ = run_git(repo, &["config", "--unset", &merge_key]).await;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Rebuilds the stash list as `to.stashes`. The stash commits are still in
+/// the object database, so each one is stored again under its own subject.
+async fn update_stashes(repo: &Path, from: &RepoState, to: &RepoState) -> Result<(), String> {
+    if from.stashes == to.stashes {
+        return Ok(());
+    }
+
+    run_git(repo, &["stash", "clear"]).await?;
+
+    for oid in to.stashes.iter().rev() {
+        let subject = run_git(repo, &["log", "-1", "--format=%s", oid]).await?;
+        run_git(repo, &["stash", "store", "-m", &subject, oid]).await?;
+    }
+
+    Ok(())
+}
+
+/// Moves the repository from the `from` state of an entry to its `to` state.
+async fn apply_state(
+    repo: &Path,
+    action: &str,
+    entry: &JournalEntry,
+    from: &RepoState,
+    to: &RepoState,
+) -> Result<(), String> {
+    let current = capture_state(repo)
+        .await
+        .ok_or_else(|| "Failed to read the repository state".to_string())?;
+
+    if current.head != from.head
+        || current.head_ref != from.head_ref
+        || current.branches != from.branches
+        || current.upstreams != from.upstreams
+        || current.stashes != from.stashes
+    {
+        return Err(format!(
+            "The repository has changed since '{}', it can no longer be undone or redone",
+            entry.operation
+        ));
+    }
+
+    let (Some(current_tree), Some(target_tree)) = (&current.index_tree, &to.index_tree) else {
+        return Err("Finish or abort the operation in progress first".to_string());
+    };
+
+    if entry.worktree {
+        if current.head.is_some() {
+            create_snapshot(repo, &format!("{} of {}", action, entry.operation)).await?;
+        }
+
+        // A two-way merge updates the files that differ between the trees and
+        // refuses to overwrite local modifications to them.
+        run_git(repo, &["read-tree", "-u", "-m", current_tree, target_tree]).await?;
+    } else {
+        // Only the index is replaced, so anything staged since would be lost.
+        if current.index_tree != from.index_tree {
+            return Err(format!(
+                "The index has changed since '{}', it can no longer be undone or redone",
+                entry.operation
+            ));
+        }
+
+        run_git(repo, &["read-tree", target_tree]).await?;
+    }
+
+    // read-tree drops the cached stat data; refresh it so unchanged files do
+    // not show up as modified. A non-zero exit only means some files differ.
+    let _ = run_git(repo, &["update-index", "-q", "--refresh"]).await;
+
+    let reason = format!("{}: {}", action, entry.operation);
+    update_refs(repo, from, to, &reason).await?;
+    update_upstreams(repo, from, to).await?;
+    update_stashes(repo, from, to).await?;
+
+    match (&to.head_ref, &to.head) {
+        (Some(head_ref), _) => {
+            run_git(repo, &["symbolic-ref", "-m", &reason, "HEAD", head_ref]).await?;
+        }
+        (None, Some(head)) => {
+            run_git(
+                repo,
+                &["update-ref", "-m", &reason, "--no-deref", "HEAD", head],
+            )
+            .await?;
+        }
+        (None, None) => {}
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_operations(repo_path: String) -> Result<Vec<JournalEntry>, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let mut entries = read_entries(repo);
+    entries.reverse();
+    Ok(entries)
+}
+
+#[tauri::command]
+pub async fn undo_last_operation(repo_path: String) -> Result<JournalEntry, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let mut entry = read_entries(repo)
+        .into_iter()
+        .rev()
+        .find(|entry| !entry.undone)
+        .ok_or_else(|| "There is nothing to undo".to_string())?;
+
+    apply_state(repo, "undo", &entry, &entry.after, &entry.before).await?;
+
+    entry.undone = true;
+    write_entry(repo, &entry)?;
+    Ok(entry)
+}
+
+#[tauri::command]
+pub async fn redo_last_operation(repo_path: String) -> Result<JournalEntry, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let mut entry = read_entries(repo)
+        .into_iter()
+        .find(|entry| entry.undone)
+        .ok_or_else(|| "There is nothing to redo".to_string())?;
+
+    apply_state(repo, "redo", &entry, &entry.before, &entry.after).await?;
+
+    entry.undone = false;
+    write_entry(repo, &entry)?;
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cherry_pick::cherry_pick_commits;
+    use crate::commands::git::{commit_changes, set_branch_upstream, stage_file};
+    use crate::commands::stash::push_stash;
+    use crate::commands::test_util::{commit_file, git, init_repo, path_str, write_file};
+
+    #[tokio::test]
+    async fn refuses_to_undo_over_a_changed_index() {
+        let repo = init_repo();
+        commit_file(repo.path(), "a.txt", "one\n", "first");
+        write_file(repo.path(), "a.txt", "two\n");
+        stage_file(path_str(&repo), "a.txt".to_string())
+            .await
+            .unwrap();
+
+        write_file(repo.path(), "b.txt", "staged outside the journal\n");
+        git(repo.path(), &["add", "b.txt"]);
+
+        let result = undo_last_operation(path_str(&repo)).await;
+        assert!(matches!(result, Err(e) if e.contains("index has changed")));
+        assert!(git(repo.path(), &["diff", "--cached", "--name-only"]).contains("b.txt"));
+    }
+
+    #[tokio::test]
+    async fn undoes_and_redoes_a_stash_push() {
+        let repo = init_repo();
+        commit_file(repo.path(), "a.txt", "one\n", "first");
+        write_file(repo.path(), "a.txt", "two\n");
+        git(repo.path(), &["add", "a.txt"]);
+
+        let stash = push_stash(path_str(&repo), Some("wip".to_string()), None, None, None)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let entry = undo_last_operation(path_str(&repo)).await.unwrap();
+        assert_eq!(entry.operation, "stash push");
+        assert_eq!(git(repo.path(), &["stash", "list"]), "");
+        assert_eq!(
+            git(repo.path(), &["diff", "--cached", "--name-only"]),
+            "a.txt"
+        );
+
+        redo_last_operation(path_str(&repo)).await.unwrap();
+        assert_eq!(git(repo.path(), &["rev-parse", "refs/stash"]), stash.hash);
+        assert_eq!(
+            git(repo.path(), &["stash", "list", "--format=%gs"]),
+            "On main: wip"
+        );
+        assert_eq!(git(repo.path(), &["diff", "--cached", "--name-only"]), "");
+    }
+
+    #[tokio::test]
+    async fn undoes_an_upstream_change() {
+        let repo = init_repo();
+        commit_file(repo.path(), "a.txt", "one\n", "first");
+        git(repo.path(), &["branch", "topic"]);
+
+        set_branch_upstream(
+            path_str(&repo),
+            "topic".to_string(),
+            Some("main".to_string()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            git(repo.path(), &["config", "branch.topic.merge"]),
+            "refs/heads/main"
+        );
+
+        let entry = undo_last_operation(path_str(&repo)).await.unwrap();
+        assert_eq!(entry.operation, "set upstream");
+        let merge = std::process::Command::new("git")
+            .args(["config", "branch.topic.merge"])
+            .current_dir(repo.path())
+            .output()
+            .unwrap();
+        assert!(!merge.status.success());
+    }
+
+    #[tokio::test]
+    async fn commit_completes_a_conflicted_cherry_pick() {
+        let repo = init_repo();
+        commit_file(repo.path(), "a.txt", "base\n", "base");
+        git(repo.path(), &["switch", "-q", "-c", "topic"]);
+        let pick = commit_file(repo.path(), "a.txt", "topic\n", "topic change");
+        git(repo.path(), &["switch", "-q", "main"]);
+        let head = commit_file(repo.path(), "a.txt", "main\n", "main change");
+
+        let result = cherry_pick_commits(path_str(&repo), vec![pick], None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(result.status, "conflict");
+
+        write_file(repo.path(), "a.txt", "resolved\n");
+        git(repo.path(), &["add", "a.txt"]);
+        commit_changes(path_str(&repo), "picked".to_string())
+            .await
+            .unwrap();
+
+        let operations = list_operations(path_str(&repo)).unwrap();
+        assert_eq!(operations[0].operation, "cherry-pick");
+        assert_eq!(operations[0].before.head.as_deref(), Some(head.as_str()));
+        assert!(!pending_file(repo.path()).exists());
+    }
+}
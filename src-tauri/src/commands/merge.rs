@@ -0,0 +1,762 @@
+use std::path::Path;
+use tokio::process::Command;
+
+use super::git::parse_status_v2;
+use super::journal::{
+    begin_pending, capture_state, discard_pending, finish_pending, record_operation,
+};
+
+#[derive(serde::Serialize)]
+pub struct MergeResult {
+    pub status: String,
+    pub head: Option<String>,
+    pub conflicts: Vec<String>,
+    pub message: String,
+}
+
+pub(crate) async fn head_oid(repo: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .arg("rev-parse")
+        .arg("--verify")
+        .arg("--quiet")
+        .arg("HEAD")
+        .current_dir(repo)
+        .output()
+        .await
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Lists the paths that still have unmerged index entries.
+pub(crate) async fn conflicted_paths(repo: &Path) -> Result<Vec<String>, String> {
+    let output = Command::new("git")
+        .arg("diff")
+        .arg("--name-only")
+        .arg("--diff-filter=U")
+        .arg("-z")
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git diff: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git diff command failed: {}", stderr));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut paths: Vec<String> = stdout
+        .split('\0')
+        .filter(|p| !p.is_empty())
+        .map(|p| p.to_string())
+        .collect();
+    paths.dedup();
+    Ok(paths)
+}
+
+#[tauri::command]
+pub async fn merge_git_branch(
+    repo_path: String,
+    branch_name: String,
+    ff_only: Option<bool>,
+    no_ff: Option<bool>,
+    squash: Option<bool>,
+    message: Option<String>,
+) -> Result<MergeResult, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    if ff_only.unwrap_or(false) && (no_ff.unwrap_or(false) || squash.unwrap_or(false)) {
+        return Err("Fast-forward only cannot be combined with no-ff or squash".to_string());
+    }
+
+    let head_before = head_oid(repo).await;
+    begin_pending(repo, "merge").await;
+
+    let mut cmd = Command::new("git");
+    cmd.arg("merge").arg("--no-edit");
+
+    if ff_only.unwrap_or(false) {
+        cmd.arg("--ff-only");
+    }
+
+    if no_ff.unwrap_or(false) {
+        cmd.arg("--no-ff");
+    }
+
+    if squash.unwrap_or(false) {
+        cmd.arg("--squash");
+    }
+
+    if let Some(msg) = message.as_deref().filter(|m| !m.trim().is_empty()) {
+        cmd.arg("-m").arg(msg);
+    }
+
+    let output = cmd
+        .arg("--end-of-options")
+        .arg(branch_name.trim())
+        .env("LC_ALL", "C")
+        .env("GIT_MERGE_AUTOEDIT", "no")
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git merge: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    if !output.status.success() {
+        let conflicts = conflicted_paths(repo).await?;
+        if conflicts.is_empty() {
+            discard_pending(repo, "merge");
+            return Err(format!("Git merge failed: {}{}", stderr, stdout));
+        }
+
+        return Ok(MergeResult {
+            status: "conflict".to_string(),
+            head: head_before,
+            conflicts,
+            message: stdout.trim().to_string(),
+        });
+    }
+
+    finish_pending(repo, "merge").await;
+
+    let head = head_oid(repo).await;
+    let status = if stdout.contains("Already up to date") {
+        "up-to-date"
+    } else if squash.unwrap_or(false) {
+        "squashed"
+    } else if stdout.contains("Fast-forward") {
+        "fast-forward"
+    } else {
+        "merged"
+    };
+
+    Ok(MergeResult {
+        status: status.to_string(),
+        head,
+        conflicts: Vec::new(),
+        message: stdout.trim().to_string(),
+    })
+}
+
+#[tauri::command]
+pub async fn abort_merge(repo_path: String) -> Result<(), String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let output = Command::new("git")
+        .arg("merge")
+        .arg("--abort")
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git merge --abort: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git merge --abort failed: {}", stderr));
+    }
+
+    discard_pending(repo, "merge");
+    Ok(())
+}
+
+#[derive(serde::Serialize)]
+pub struct ConflictEntry {
+    pub path: String,
+    pub conflict_type: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct ConflictRegion {
+    pub start_line: usize,
+    pub end_line: usize,
+    pub ours_label: String,
+    pub theirs_label: String,
+    pub ours: Vec<String>,
+    pub base: Option<Vec<String>>,
+    pub theirs: Vec<String>,
+}
+
+#[derive(serde::Serialize)]
+pub struct ConflictDetails {
+    pub path: String,
+    pub conflict_type: String,
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+    pub worktree: Option<String>,
+    pub regions: Vec<ConflictRegion>,
+}
+
+fn conflict_relative_path(repo: &Path, file_path: &str) -> String {
+    Path::new(file_path)
+        .strip_prefix(repo)
+        .ok()
+        .and_then(|p| p.to_str())
+        .unwrap_or(file_path)
+        .replace('\\', "/")
+}
+
+async fn read_conflicts(repo: &Path) -> Result<Vec<ConflictEntry>, String> {
+    let output = Command::new("git")
+        .arg("status")
+        .arg("--porcelain=v2")
+        .arg("-z")
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git status: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git status command failed: {}", stderr));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(parse_status_v2(&stdout)
+        .entries
+        .into_iter()
+        .filter(|entry| entry.kind == "unmerged")
+        .map(|entry| ConflictEntry {
+            path: entry.path,
+            conflict_type: entry.conflict.unwrap_or_else(|| "unknown".to_string()),
+        })
+        .collect())
+}
+
+/// Reads the blob stored at index `stage` (1 base, 2 ours, 3 theirs).
+async fn read_stage(repo: &Path, stage: u8, path: &str) -> Option<String> {
+    let output = Command::new("git")
+        .arg("show")
+        .arg(format!(":{}:{}", stage, path))
+        .current_dir(repo)
+        .output()
+        .await
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Returns the label after a seven-character conflict marker. Longer runs
+/// are content, e.g. the markers git nests inside a recursive merge base.
+fn conflict_marker<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    let rest = line.strip_prefix(marker)?;
+    if rest.is_empty() || rest.starts_with(' ') {
+        Some(rest.trim())
+    } else {
+        None
+    }
+}
+
+/// Parses `<<<<<<<`/`|||||||`/`=======`/`>>>>>>>` regions, including the
+/// diff3 base section. Line numbers are 1-based and inclusive.
+fn parse_conflict_regions(content: &str) -> Vec<ConflictRegion> {
+    enum Section {
+        Ours,
+        Base,
+        Theirs,
+    }
+
+    let mut regions = Vec::new();
+    let mut current: Option<(ConflictRegion, Section)> = None;
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+
+        if let Some(label) = conflict_marker(line, "<<<<<<<") {
+            current = Some((
+                ConflictRegion {
+                    start_line: line_number,
+                    end_line: line_number,
+                    ours_label: label.to_string(),
+                    theirs_label: String::new(),
+                    ours: Vec::new(),
+                    base: None,
+                    theirs: Vec::new(),
+                },
+                Section::Ours,
+            ));
+            continue;
+        }
+
+        let Some((region, section)) = current.as_mut() else {
+            continue;
+        };
+
+        if conflict_marker(line, "|||||||").is_some() && matches!(section, Section::Ours) {
+            region.base = Some(Vec::new());
+            *section = Section::Base;
+        } else if conflict_marker(line, "=======").is_some() && !matches!(section, Section::Theirs)
+        {
+            *section = Section::Theirs;
+        } else if let Some(label) =
+            conflict_marker(line, ">>>>>>>").filter(|_| matches!(section, Section::Theirs))
+        {
+            region.theirs_label = label.to_string();
+            region.end_line = line_number;
+            if let Some((region, _)) = current.take() {
+                regions.push(region);
+            }
+        } else {
+            match section {
+                Section::Ours => region.ours.push(line.to_string()),
+                Section::Base => {
+                    if let Some(base) = region.base.as_mut() {
+                        base.push(line.to_string());
+                    }
+                }
+                Section::Theirs => region.theirs.push(line.to_string()),
+            }
+        }
+    }
+
+    regions
+}
+
+#[tauri::command]
+pub async fn list_conflicts(repo_path: String) -> Result<Vec<ConflictEntry>, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    read_conflicts(repo).await
+}
+
+#[tauri::command]
+pub async fn get_conflict_details(
+    repo_path: String,
+    file_path: String,
+) -> Result<ConflictDetails, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let path = conflict_relative_path(repo, &file_path);
+    let entry = read_conflicts(repo)
+        .await?
+        .into_iter()
+        .find(|entry| entry.path == path)
+        .ok_or_else(|| format!("'{}' is not in a conflicted state", path))?;
+
+    let worktree = std::fs::read(repo.join(&path))
+        .ok()
+        .map(|bytes| String::from_utf8_lossy(&bytes).to_string());
+    let regions = worktree
+        .as_deref()
+        .map(parse_conflict_regions)
+        .unwrap_or_default();
+
+    Ok(ConflictDetails {
+        base: read_stage(repo, 1, &path).await,
+        ours: read_stage(repo, 2, &path).await,
+        theirs: read_stage(repo, 3, &path).await,
+        path,
+        conflict_type: entry.conflict_type,
+        worktree,
+        regions,
+    })
+}
+
+#[tauri::command]
+pub async fn resolve_conflict_take(
+    repo_path: String,
+    file_path: String,
+    side: String,
+) -> Result<(), String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let (flag, stage) = match side.as_str() {
+        "ours" => ("--ours", 2),
+        "theirs" => ("--theirs", 3),
+        _ => return Err(format!("Unknown conflict side '{}'", side)),
+    };
+
+    let path = conflict_relative_path(repo, &file_path);
+    let before = capture_state(repo).await;
+
+    // Taking a side that deleted the file resolves the conflict as a deletion.
+    let output = if read_stage(repo, stage, &path).await.is_none() {
+        Command::new("git")
+            .arg("rm")
+            .arg("--quiet")
+            .arg("--")
+            .arg(&path)
+            .current_dir(repo)
+            .output()
+            .await
+            .map_err(|e| format!("Failed to execute git rm: {}", e))?
+    } else {
+        let output = Command::new("git")
+            .arg("checkout")
+            .arg(flag)
+            .arg("--")
+            .arg(&path)
+            .current_dir(repo)
+            .output()
+            .await
+            .map_err(|e| format!("Failed to execute git checkout {}: {}", flag, e))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Git checkout {} failed: {}", flag, stderr));
+        }
+
+        Command::new("git")
+            .arg("add")
+            .arg("--")
+            .arg(&path)
+            .current_dir(repo)
+            .output()
+            .await
+            .map_err(|e| format!("Failed to execute git add: {}", e))?
+    };
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Resolving conflict failed: {}", stderr));
+    }
+
+    record_operation(repo, "resolve conflict", true, before).await;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn mark_conflict_resolved(
+    repo_path: String,
+    file_path: String,
+    force: Option<bool>,
+) -> Result<(), String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let path = conflict_relative_path(repo, &file_path);
+
+    if !force.unwrap_or(false) {
+        if let Ok(bytes) = std::fs::read(repo.join(&path)) {
+            let content = String::from_utf8_lossy(&bytes);
+            if !parse_conflict_regions(&content).is_empty() {
+                return Err(format!("'{}' still contains conflict markers", path));
+            }
+        }
+    }
+
+    let before = capture_state(repo).await;
+
+    // `-A` also records a resolution that deletes the file.
+    let output = Command::new("git")
+        .arg("add")
+        .arg("-A")
+        .arg("--")
+        .arg(&path)
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git add: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git add failed: {}", stderr));
+    }
+
+    record_operation(repo, "mark resolved", false, before).await;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_util::{commit_file, git, init_repo, path_str, write_file};
+    use tempfile::TempDir;
+
+    /// A repository whose `feature` branch adds `feature.txt` on top of
+    /// `main`, checked out on `main`.
+    fn repo_with_feature() -> TempDir {
+        let repo = init_repo();
+        commit_file(repo.path(), "a.txt", "one\n", "base");
+        git(repo.path(), &["switch", "-q", "-c", "feature"]);
+        commit_file(repo.path(), "feature.txt", "feature\n", "feature");
+        git(repo.path(), &["switch", "-q", "main"]);
+        repo
+    }
+
+    async fn merge(repo: &TempDir, no_ff: bool, squash: bool) -> MergeResult {
+        merge_git_branch(
+            path_str(repo),
+            "feature".to_string(),
+            None,
+            Some(no_ff),
+            Some(squash),
+            None,
+        )
+        .await
+        .unwrap_or_else(|e| panic!("merge failed: {}", e))
+    }
+
+    #[tokio::test]
+    async fn fast_forwards_when_possible() {
+        let repo = repo_with_feature();
+        let feature = git(repo.path(), &["rev-parse", "feature"]);
+
+        let result = merge(&repo, false, false).await;
+
+        assert_eq!(result.status, "fast-forward");
+        assert_eq!(result.head.as_deref(), Some(feature.as_str()));
+        assert!(result.conflicts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn no_ff_creates_a_merge_commit() {
+        let repo = repo_with_feature();
+
+        let result = merge(&repo, true, false).await;
+
+        assert_eq!(result.status, "merged");
+        let parents = git(repo.path(), &["rev-list", "--parents", "-n", "1", "HEAD"]);
+        assert_eq!(parents.split(' ').count(), 3);
+    }
+
+    #[tokio::test]
+    async fn squash_stages_the_changes_without_committing() {
+        let repo = repo_with_feature();
+        let head = git(repo.path(), &["rev-parse", "HEAD"]);
+
+        let result = merge(&repo, false, true).await;
+
+        assert_eq!(result.status, "squashed");
+        assert_eq!(result.head.as_deref(), Some(head.as_str()));
+        assert_eq!(
+            git(repo.path(), &["diff", "--cached", "--name-only"]),
+            "feature.txt"
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_conflicted_paths() {
+        let repo = repo_with_feature();
+        git(repo.path(), &["switch", "-q", "feature"]);
+        commit_file(repo.path(), "a.txt", "theirs\n", "feature edit");
+        git(repo.path(), &["switch", "-q", "main"]);
+        let head = commit_file(repo.path(), "a.txt", "ours\n", "main edit");
+
+        let result = merge(&repo, false, false).await;
+
+        assert_eq!(result.status, "conflict");
+        assert_eq!(result.head.as_deref(), Some(head.as_str()));
+        assert_eq!(result.conflicts, ["a.txt"]);
+
+        abort_merge(path_str(&repo)).await.unwrap();
+        assert!(conflicted_paths(repo.path()).await.unwrap().is_empty());
+    }
+
+    type Region<'a> = (usize, usize, &'a str, &'a str, Vec<&'a str>);
+
+    fn summarize(regions: &[ConflictRegion]) -> Vec<Region<'_>> {
+        regions
+            .iter()
+            .map(|r| {
+                let sides = r.ours.iter().chain(&r.theirs).map(String::as_str);
+                (
+                    r.start_line,
+                    r.end_line,
+                    r.ours_label.as_str(),
+                    r.theirs_label.as_str(),
+                    sides.collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_diff3_conflicts() {
+        let content =
+            "a\n<<<<<<< HEAD\nours\n||||||| 1390709\nbase\n=======\ntheirs\n>>>>>>> side\nz\n";
+
+        let regions = parse_conflict_regions(content);
+
+        assert_eq!(
+            summarize(&regions),
+            [(2, 8, "HEAD", "side", vec!["ours", "theirs"])]
+        );
+        assert_eq!(regions[0].base.as_deref(), Some(&["base".to_string()][..]));
+    }
+
+    #[test]
+    fn keeps_nested_recursive_merge_markers_as_content() {
+        // A criss-cross merge with diff3 conflict style.
+        let content = "\
+1
+<<<<<<< HEAD
+AB
+||||||| merged common ancestors
+<<<<<<<<< Temporary merge branch 1
+B
+||||||||| f83a6cb
+2
+=========
+A
+>>>>>>>>> Temporary merge branch 2
+=======
+BA
+>>>>>>> B
+3
+";
+
+        let regions = parse_conflict_regions(content);
+
+        assert_eq!(
+            summarize(&regions),
+            [(2, 14, "HEAD", "B", vec!["AB", "BA"])]
+        );
+        assert_eq!(regions[0].base.as_ref().map(Vec::len), Some(7));
+        assert_eq!(
+            regions[0].base.as_ref().unwrap()[0],
+            "<<<<<<<<< Temporary merge branch 1"
+        );
+    }
+
+    #[test]
+    fn ignores_unterminated_conflicts() {
+        let content = "<<<<<<< HEAD\nlost\n=======\n<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> side\n<<<<<<< HEAD\ntrailing\n";
+
+        let regions = parse_conflict_regions(content);
+
+        assert_eq!(
+            summarize(&regions),
+            [(4, 8, "HEAD", "side", vec!["ours", "theirs"])]
+        );
+    }
+
+    #[test]
+    fn parses_crlf_conflicts() {
+        let content = "a\r\n<<<<<<< HEAD\r\nours\r\n=======\r\ntheirs\r\n>>>>>>> side\r\n";
+
+        let regions = parse_conflict_regions(content);
+
+        assert_eq!(
+            summarize(&regions),
+            [(2, 6, "HEAD", "side", vec!["ours", "theirs"])]
+        );
+        assert!(regions[0].base.is_none());
+    }
+
+    #[tokio::test]
+    async fn taking_a_side_clears_the_conflict_stages() {
+        let repo = init_repo();
+        let commit_both = |content: &str, message: &str| {
+            write_file(repo.path(), "a.txt", content);
+            write_file(repo.path(), "b.txt", content);
+            git(repo.path(), &["add", "a.txt", "b.txt"]);
+            git(repo.path(), &["commit", "-q", "-m", message]);
+        };
+        commit_both("base\n", "base");
+        git(repo.path(), &["switch", "-q", "-c", "feature"]);
+        commit_both("theirs\n", "feature edit");
+        git(repo.path(), &["switch", "-q", "main"]);
+        commit_both("ours\n", "main edit");
+        merge(&repo, false, false).await;
+
+        let details = get_conflict_details(path_str(&repo), "a.txt".to_string())
+            .await
+            .unwrap_or_else(|e| panic!("details failed: {}", e));
+        assert_eq!(details.conflict_type, "both-modified");
+        assert_eq!(details.base.as_deref(), Some("base\n"));
+        assert_eq!(details.regions.len(), 1);
+
+        resolve_conflict_take(path_str(&repo), "a.txt".to_string(), "ours".to_string())
+            .await
+            .unwrap();
+        resolve_conflict_take(path_str(&repo), "b.txt".to_string(), "theirs".to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(git(repo.path(), &["ls-files", "--unmerged"]), "");
+        assert_eq!(git(repo.path(), &["show", ":a.txt"]), "ours");
+        assert_eq!(git(repo.path(), &["show", ":b.txt"]), "theirs");
+        assert!(list_conflicts(path_str(&repo)).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn branch_names_are_never_parsed_as_options() {
+        let repo = repo_with_feature();
+        let feature = git(repo.path(), &["rev-parse", "feature"]);
+        git(
+            repo.path(),
+            &["update-ref", "refs/heads/--squash", &feature],
+        );
+
+        let result = merge_git_branch(
+            path_str(&repo),
+            "--squash".to_string(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap_or_else(|e| panic!("merge failed: {}", e));
+
+        assert_eq!(result.status, "fast-forward");
+        assert_eq!(result.head.as_deref(), Some(feature.as_str()));
+    }
+}
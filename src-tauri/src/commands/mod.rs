@@ -1,4 +1,19 @@
+pub mod cherry_pick;
+pub mod diff;
+pub mod discard;
 pub mod fs;
 pub mod git;
 pub mod global_search;
+pub mod graph;
+pub mod journal;
+pub mod merge;
+pub mod patch;
+pub mod rebase;
+pub mod reflog;
+pub mod reset;
+pub mod stash;
 pub mod system;
+pub mod tags;
+
+#[cfg(test)]
+mod test_util;
@@ -0,0 +1,676 @@
+use std::path::Path;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use super::diff::PATCH_FORMAT_ARGS;
+use super::journal::{capture_state, record_operation};
+
+#[derive(Clone, Copy, serde::Deserialize)]
+pub struct LineRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl LineRange {
+    fn contains(&self, line: u32) -> bool {
+        self.start <= line && line <= self.end
+    }
+}
+
+/// Part of a file diff to act on. `hunks` are indexes into the hunks of the
+/// diff, while `old_lines` and `new_lines` pick removed and added lines by
+/// the line numbers shown next to them.
+#[derive(Default, serde::Deserialize)]
+pub struct PatchSelection {
+    #[serde(default)]
+    pub hunks: Vec<usize>,
+    #[serde(default)]
+    pub old_lines: Vec<LineRange>,
+    #[serde(default)]
+    pub new_lines: Vec<LineRange>,
+}
+
+struct Hunk {
+    old_start: u32,
+    new_start: u32,
+    section: String,
+    lines: Vec<String>,
+}
+
+struct FilePatch {
+    header: Vec<String>,
+    hunks: Vec<Hunk>,
+}
+
+/// Maps `context_lines` to the `-U` argument shared by every diff the UI
+/// selects from, so hunk indexes line up with `get_git_diff`.
+pub(crate) fn unified_arg(context_lines: Option<u32>) -> String {
+    format!("-U{}", context_lines.unwrap_or(999999))
+}
+
+fn parse_hunk_header(line: &str) -> Option<(u32, u32, String)> {
+    let rest = line.strip_prefix("@@ -")?;
+    let (ranges, section) = rest.split_once(" @@")?;
+    let (old_range, new_range) = ranges.split_once(" +")?;
+
+    let start_of = |range: &str| range.split(',').next().and_then(|s| s.parse::<u32>().ok());
+
+    Some((
+        start_of(old_range)?,
+        start_of(new_range)?,
+        section.to_string(),
+    ))
+}
+
+fn parse_file_patch(diff: &str) -> FilePatch {
+    let mut header = Vec::new();
+    let mut hunks: Vec<Hunk> = Vec::new();
+
+    for line in diff.lines() {
+        if line.starts_with("diff --git") && !hunks.is_empty() {
+            // Only the first file of the diff is relevant.
+            break;
+        }
+
+        if let Some((old_start, new_start, section)) = parse_hunk_header(line) {
+            hunks.push(Hunk {
+                old_start,
+                new_start,
+                section,
+                lines: Vec::new(),
+            });
+            continue;
+        }
+
+        match hunks.last_mut() {
+            Some(hunk) => hunk.lines.push(line.to_string()),
+            None => header.push(line.to_string()),
+        }
+    }
+
+    FilePatch { header, hunks }
+}
+
+/// Builds a patch containing only the selected changes of `diff`.
+///
+/// With `reverse` unset the patch is meant for a forward `git apply`: unselected
+/// removals become context and unselected additions are dropped. With `reverse`
+/// set it is meant for `git apply --reverse`, so the roles are swapped and the
+/// new side of the diff stays the preimage.
+pub(crate) fn select_patch(
+    diff: &str,
+    selection: &PatchSelection,
+    reverse: bool,
+) -> Option<String> {
+    let patch = parse_file_patch(diff);
+
+    let mut output_hunks: Vec<String> = Vec::new();
+    let mut delta: i64 = 0;
+    let mut all_selected = true;
+
+    for (index, hunk) in patch.hunks.iter().enumerate() {
+        let whole_hunk = selection.hunks.contains(&index);
+        let mut old_line = hunk.old_start;
+        let mut new_line = hunk.new_start;
+
+        let mut lines: Vec<String> = Vec::new();
+        let mut old_count: u32 = 0;
+        let mut new_count: u32 = 0;
+        let mut has_changes = false;
+        let mut last_emitted = false;
+
+        for line in &hunk.lines {
+            if line.starts_with('\\') {
+                if last_emitted {
+                    lines.push(line.clone());
+                }
+                continue;
+            }
+
+            let (prefix, content) = line.split_at(line.len().min(1));
+
+            match prefix {
+                "-" => {
+                    let selected =
+                        whole_hunk || selection.old_lines.iter().any(|r| r.contains(old_line));
+                    old_line += 1;
+
+                    if selected {
+                        lines.push(line.clone());
+                        old_count += 1;
+                        has_changes = true;
+                        last_emitted = true;
+                    } else if reverse {
+                        all_selected = false;
+                        last_emitted = false;
+                    } else {
+                        all_selected = false;
+                        lines.push(format!(" {}", content));
+                        old_count += 1;
+                        new_count += 1;
+                        last_emitted = true;
+                    }
+                }
+                "+" => {
+                    let selected =
+                        whole_hunk || selection.new_lines.iter().any(|r| r.contains(new_line));
+                    new_line += 1;
+
+                    if selected {
+                        lines.push(line.clone());
+                        new_count += 1;
+                        has_changes = true;
+                        last_emitted = true;
+                    } else if reverse {
+                        all_selected = false;
+                        lines.push(format!(" {}", content));
+                        old_count += 1;
+                        new_count += 1;
+                        last_emitted = true;
+                    } else {
+                        all_selected = false;
+                        last_emitted = false;
+                    }
+                }
+                _ => {
+                    old_line += 1;
+                    new_line += 1;
+                    lines.push(format!(" {}", content));
+                    old_count += 1;
+                    new_count += 1;
+                    last_emitted = true;
+                }
+            }
+        }
+
+        if !has_changes {
+            continue;
+        }
+
+        // The preimage keeps its position; the other side shifts by the
+        // changes of the hunks emitted before this one.
+        let (pre_start, pre_count, post_count) = if reverse {
+            (hunk.new_start, new_count, old_count)
+        } else {
+            (hunk.old_start, old_count, new_count)
+        };
+
+        // An empty range names the line before the change, as in `-3,0`, so
+        // shift the first affected line and convert back for an empty result.
+        let first_line = if pre_count == 0 {
+            pre_start as i64 + 1
+        } else {
+            pre_start as i64
+        } + delta;
+        let post_start = if post_count == 0 {
+            (first_line - 1).max(0)
+        } else {
+            first_line.max(1)
+        } as u32;
+        delta += post_count as i64 - pre_count as i64;
+
+        let (old_start, new_start) = if reverse {
+            (post_start, pre_start)
+        } else {
+            (pre_start, post_start)
+        };
+
+        let mut text = format!(
+            "@@ -{},{} +{},{} @@{}\n",
+            old_start, old_count, new_start, new_count, hunk.section
+        );
+        for line in lines {
+            text.push_str(&line);
+            text.push('\n');
+        }
+        output_hunks.push(text);
+    }
+
+    if output_hunks.is_empty() {
+        return None;
+    }
+
+    let mut output = String::new();
+    for line in &patch.header {
+        // A partial selection can neither create nor delete the file on the
+        // side that is being rewritten, so the header has to describe a
+        // plain modification instead.
+        if !all_selected {
+            if !reverse && line.starts_with("deleted file mode") {
+                continue;
+            }
+            if reverse && line.starts_with("new file mode") {
+                continue;
+            }
+            if !reverse && line == "+++ /dev/null" {
+                output.push_str(&format!("+++ b/{}\n", patch_path(&patch.header)));
+                continue;
+            }
+            if reverse && line == "--- /dev/null" {
+                output.push_str(&format!("--- a/{}\n", patch_path(&patch.header)));
+                continue;
+            }
+        }
+        output.push_str(line);
+        output.push('\n');
+    }
+
+    for hunk in output_hunks {
+        output.push_str(&hunk);
+    }
+
+    Some(output)
+}
+
+fn patch_path(header: &[String]) -> String {
+    header
+        .iter()
+        .find_map(|line| {
+            line.strip_prefix("+++ b/")
+                .or_else(|| line.strip_prefix("--- a/"))
+        })
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Feeds `patch` to `git apply` with the given extra arguments.
+pub(crate) async fn apply_patch(repo: &Path, patch: &str, args: &[&str]) -> Result<(), String> {
+    let mut child = Command::new("git")
+        .arg("apply")
+        .arg("--whitespace=nowarn")
+        .args(args)
+        .arg("-")
+        .current_dir(repo)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to execute git apply: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(patch.as_bytes())
+            .await
+            .map_err(|e| format!("Failed to write patch to git apply: {}", e))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|e| format!("Failed to wait for git apply: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git apply failed: {}", stderr));
+    }
+
+    Ok(())
+}
+
+/// Returns the diff of `relative_path` that staging (`cached` unset) or
+/// unstaging (`cached` set) selects from.
+pub(crate) async fn file_diff(
+    repo: &Path,
+    relative_path: &str,
+    cached: bool,
+    context_lines: Option<u32>,
+) -> Result<String, String> {
+    let mut cmd = Command::new("git");
+    cmd.arg("--no-pager")
+        .arg("diff")
+        .args(PATCH_FORMAT_ARGS)
+        .arg(unified_arg(context_lines));
+
+    if cached {
+        cmd.arg("--cached");
+    }
+
+    let output = cmd
+        .arg("--")
+        .arg(relative_path)
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git diff: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git diff command failed: {}", stderr));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    if !stdout.is_empty() || cached {
+        return Ok(stdout);
+    }
+
+    // Untracked files have no diff against the index; show them as new files.
+    let tracked = Command::new("git")
+        .arg("ls-files")
+        .arg("--error-unmatch")
+        .arg("--")
+        .arg(relative_path)
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git ls-files: {}", e))?;
+
+    if tracked.status.success() || !repo.join(relative_path).is_file() {
+        return Ok(stdout);
+    }
+
+    let output = Command::new("git")
+        .arg("--no-pager")
+        .arg("diff")
+        .args(PATCH_FORMAT_ARGS)
+        .arg("--no-index")
+        .arg(unified_arg(context_lines))
+        .arg("--")
+        .arg("/dev/null")
+        .arg(relative_path)
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git diff --no-index: {}", e))?;
+
+    // `--no-index` exits with 1 when the files differ.
+    if output.status.code() != Some(0) && output.status.code() != Some(1) {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git diff command failed: {}", stderr));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[tauri::command]
+pub async fn stage_hunks(
+    repo_path: String,
+    file_path: String,
+    selection: PatchSelection,
+    context_lines: Option<u32>,
+) -> Result<(), String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let relative_path = Path::new(&file_path)
+        .strip_prefix(repo)
+        .ok()
+        .and_then(|p| p.to_str())
+        .unwrap_or(&file_path);
+
+    let diff = file_diff(repo, relative_path, false, context_lines).await?;
+    if diff.contains("\nBinary files ") || diff.contains("\nGIT binary patch") {
+        return Err("Cannot stage part of a binary file".to_string());
+    }
+
+    let patch = select_patch(&diff, &selection, false)
+        .ok_or_else(|| "No changes selected to stage".to_string())?;
+
+    let mut args = vec!["--cached"];
+    if context_lines == Some(0) {
+        args.push("--unidiff-zero");
+    }
+
+    let before = capture_state(repo).await;
+    apply_patch(repo, &patch, &args).await?;
+    record_operation(repo, "stage hunks", false, before).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn unstage_hunks(
+    repo_path: String,
+    file_path: String,
+    selection: PatchSelection,
+    context_lines: Option<u32>,
+) -> Result<(), String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let relative_path = Path::new(&file_path)
+        .strip_prefix(repo)
+        .ok()
+        .and_then(|p| p.to_str())
+        .unwrap_or(&file_path);
+
+    let diff = file_diff(repo, relative_path, true, context_lines).await?;
+    if diff.contains("\nBinary files ") || diff.contains("\nGIT binary patch") {
+        return Err("Cannot unstage part of a binary file".to_string());
+    }
+
+    let patch = select_patch(&diff, &selection, true)
+        .ok_or_else(|| "No changes selected to unstage".to_string())?;
+
+    let mut args = vec!["--cached", "--reverse"];
+    if context_lines == Some(0) {
+        args.push("--unidiff-zero");
+    }
+
+    let before = capture_state(repo).await;
+    apply_patch(repo, &patch, &args).await?;
+    record_operation(repo, "unstage hunks", false, before).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_util::{commit_file, git, init_repo, path_str, write_file};
+
+    const HEADER: &str = "diff --git a/f b/f\n\
+        index f00c965..55646da 100644\n\
+        --- a/f\n\
+        +++ b/f\n";
+
+    /// `git diff -U1` after inserting two lines, deleting `5` and editing `10`.
+    fn diff_u1() -> String {
+        format!(
+            "{HEADER}@@ -2,5 +2,6 @@\n 2\n+new a\n+new b\n 3\n 4\n-5\n 6\n\
+             @@ -9,2 +10,2 @@\n 9\n-10\n+ten\n"
+        )
+    }
+
+    /// The same change with `-U0`.
+    fn diff_u0() -> String {
+        format!(
+            "{HEADER}@@ -2,0 +3,2 @@\n+new a\n+new b\n\
+             @@ -5 +6,0 @@\n-5\n\
+             @@ -10 +11 @@\n-10\n+ten\n"
+        )
+    }
+
+    fn hunks(hunks: &[usize]) -> PatchSelection {
+        PatchSelection {
+            hunks: hunks.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    fn range(start: u32, end: u32) -> Vec<LineRange> {
+        vec![LineRange { start, end }]
+    }
+
+    #[test]
+    fn selects_whole_hunk_and_shifts_start() {
+        let patch = select_patch(&diff_u1(), &hunks(&[1]), false).unwrap();
+        assert_eq!(patch, format!("{HEADER}@@ -9,2 +9,2 @@\n 9\n-10\n+ten\n"));
+    }
+
+    #[test]
+    fn selects_single_added_line() {
+        let selection = PatchSelection {
+            new_lines: range(3, 3),
+            ..Default::default()
+        };
+
+        // The unselected addition is dropped and the unselected removal
+        // becomes context.
+        let patch = select_patch(&diff_u1(), &selection, false).unwrap();
+        assert_eq!(
+            patch,
+            format!("{HEADER}@@ -2,5 +2,6 @@\n 2\n+new a\n 3\n 4\n 5\n 6\n")
+        );
+    }
+
+    #[test]
+    fn reverse_selection_keeps_unselected_additions_as_context() {
+        let selection = PatchSelection {
+            old_lines: range(5, 5),
+            ..Default::default()
+        };
+
+        let patch = select_patch(&diff_u1(), &selection, true).unwrap();
+        assert_eq!(
+            patch,
+            format!("{HEADER}@@ -2,7 +2,6 @@\n 2\n new a\n new b\n 3\n 4\n-5\n 6\n")
+        );
+    }
+
+    #[test]
+    fn zero_context_ranges_match_git() {
+        let patch = select_patch(&diff_u0(), &hunks(&[0, 1, 2]), false).unwrap();
+        assert_eq!(
+            patch,
+            format!(
+                "{HEADER}@@ -2,0 +3,2 @@\n+new a\n+new b\n\
+                 @@ -5,1 +6,0 @@\n-5\n\
+                 @@ -10,1 +11,1 @@\n-10\n+ten\n"
+            )
+        );
+    }
+
+    #[test]
+    fn zero_context_deletion_names_the_line_before() {
+        let patch = select_patch(&diff_u0(), &hunks(&[1]), false).unwrap();
+        assert_eq!(patch, format!("{HEADER}@@ -5,1 +4,0 @@\n-5\n"));
+
+        let patch = select_patch(&diff_u0(), &hunks(&[2]), false).unwrap();
+        assert_eq!(patch, format!("{HEADER}@@ -10,1 +10,1 @@\n-10\n+ten\n"));
+    }
+
+    #[test]
+    fn zero_context_reverse_shifts_by_reverted_hunks() {
+        let patch = select_patch(&diff_u0(), &hunks(&[0, 2]), true).unwrap();
+        assert_eq!(
+            patch,
+            format!(
+                "{HEADER}@@ -2,0 +3,2 @@\n+new a\n+new b\n\
+                 @@ -9,1 +11,1 @@\n-10\n+ten\n"
+            )
+        );
+    }
+
+    #[test]
+    fn keeps_no_newline_marker_of_emitted_lines_only() {
+        let diff = "diff --git a/g b/g\n--- a/g\n+++ b/g\n\
+            @@ -1,2 +1,2 @@\n 1\n-2\n\\ No newline at end of file\n+3\n\\ No newline at end of file\n";
+        let selection = PatchSelection {
+            old_lines: range(2, 2),
+            ..Default::default()
+        };
+
+        let patch = select_patch(diff, &selection, false).unwrap();
+        assert_eq!(
+            patch,
+            "diff --git a/g b/g\n--- a/g\n+++ b/g\n\
+             @@ -1,2 +1,1 @@\n 1\n-2\n\\ No newline at end of file\n"
+        );
+    }
+
+    #[test]
+    fn partial_deletion_becomes_a_modification() {
+        let diff = "diff --git a/d b/d\ndeleted file mode 100644\nindex 1234567..0000000\n\
+            --- a/d\n+++ /dev/null\n@@ -1,2 +0,0 @@\n-x\n-y\n";
+        let selection = PatchSelection {
+            old_lines: range(1, 1),
+            ..Default::default()
+        };
+
+        let patch = select_patch(diff, &selection, false).unwrap();
+        assert_eq!(
+            patch,
+            "diff --git a/d b/d\nindex 1234567..0000000\n--- a/d\n+++ b/d\n\
+             @@ -1,2 +1,1 @@\n-x\n y\n"
+        );
+    }
+
+    #[test]
+    fn empty_selection_yields_no_patch() {
+        assert!(select_patch(&diff_u1(), &PatchSelection::default(), false).is_none());
+        assert!(select_patch(&diff_u1(), &hunks(&[5]), false).is_none());
+    }
+
+    fn numbered_repo() -> tempfile::TempDir {
+        let repo = init_repo();
+        commit_file(
+            repo.path(),
+            "f",
+            "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n",
+            "numbers",
+        );
+        write_file(
+            repo.path(),
+            "f",
+            "1\n2\nnew a\nnew b\n3\n4\n6\n7\n8\n9\nten\n",
+        );
+        repo
+    }
+
+    #[tokio::test]
+    async fn stages_lines_regardless_of_diff_prefix_config() {
+        let repo = numbered_repo();
+        git(repo.path(), &["config", "diff.noprefix", "true"]);
+        git(repo.path(), &["config", "diff.mnemonicPrefix", "true"]);
+
+        let selection = PatchSelection {
+            new_lines: range(3, 3),
+            ..Default::default()
+        };
+        stage_hunks(path_str(&repo), "f".into(), selection, None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            git(repo.path(), &["show", ":f"]),
+            "1\n2\nnew a\n3\n4\n5\n6\n7\n8\n9\n10"
+        );
+    }
+
+    #[tokio::test]
+    async fn stages_and_unstages_zero_context_hunks() {
+        let repo = numbered_repo();
+
+        stage_hunks(path_str(&repo), "f".into(), hunks(&[1, 2]), Some(0))
+            .await
+            .unwrap();
+        assert_eq!(
+            git(repo.path(), &["show", ":f"]),
+            "1\n2\n3\n4\n6\n7\n8\n9\nten"
+        );
+
+        // The staged diff now has the deletion as hunk 0.
+        unstage_hunks(path_str(&repo), "f".into(), hunks(&[0]), Some(0))
+            .await
+            .unwrap();
+        assert_eq!(
+            git(repo.path(), &["show", ":f"]),
+            "1\n2\n3\n4\n5\n6\n7\n8\n9\nten"
+        );
+    }
+}
@@ -0,0 +1,869 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use tokio::process::Command;
+
+use super::journal::{begin_pending, discard_pending, finish_pending};
+use super::merge::{conflicted_paths, head_oid};
+
+#[derive(serde::Serialize)]
+pub struct RebaseProgress {
+    pub current_step: u32,
+    pub total_steps: u32,
+    pub remaining: u32,
+    pub stopped_at: Option<String>,
+    pub stopped_subject: Option<String>,
+    pub head_name: Option<String>,
+    pub onto: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+pub struct RebaseResult {
+    pub status: String,
+    pub head: Option<String>,
+    pub progress: Option<RebaseProgress>,
+    pub conflicts: Vec<String>,
+    pub message: String,
+}
+
+#[derive(serde::Deserialize)]
+pub struct RebaseTodoEntry {
+    pub action: String,
+    pub hash: String,
+    pub message: Option<String>,
+}
+
+const SEQUENCE_EDITOR_FLAG: &str = "--git-sequence-editor";
+const MESSAGE_EDITOR_FLAG: &str = "--git-message-editor";
+const PLAN_ENV: &str = "GIT_CLIENT_REBASE_PLAN";
+
+/// Where an interactive rebase keeps the todo list and the commit messages
+/// handed to the editor invocations.
+fn plan_dir(repo: &Path) -> PathBuf {
+    repo.join(".git").join("git-client").join("rebase")
+}
+
+/// Removes the plan. A leftover plan would hand its todo list to the next
+/// rebase, so failing to remove it is an error.
+fn clear_plan(repo: &Path) -> Result<(), String> {
+    let dir = plan_dir(repo);
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)
+            .map_err(|e| format!("Failed to remove rebase plan: {}", e))?;
+    }
+    Ok(())
+}
+
+fn editor_command(flag: &str) -> Result<String, String> {
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Failed to locate application binary: {}", e))?;
+    // Git runs editors through the shell, so quote the path.
+    let quoted = exe.to_string_lossy().replace('\'', "'\\''");
+    // Under `cargo test` the binary is the test harness, which only handles
+    // the editor flags in the `acts_as_editor` test.
+    #[cfg(test)]
+    let flag = format!("--exact --quiet {} -- {}", tests::EDITOR_TEST, flag);
+    Ok(format!("'{}' {}", quoted, flag))
+}
+
+/// Points git's sequence and message editors back at this binary when an
+/// interactive plan is active, and disables the editor otherwise.
+fn set_editor_env(cmd: &mut Command, repo: &Path) -> Result<(), String> {
+    let dir = plan_dir(repo);
+
+    if dir.is_dir() {
+        cmd.env("GIT_SEQUENCE_EDITOR", editor_command(SEQUENCE_EDITOR_FLAG)?)
+            .env("GIT_EDITOR", editor_command(MESSAGE_EDITOR_FLAG)?)
+            .env(PLAN_ENV, dir);
+    } else {
+        cmd.env("GIT_EDITOR", "true");
+    }
+
+    Ok(())
+}
+
+/// Handles the process being launched by git as an editor. Returns the exit
+/// code when it was, or `None` for a normal application start.
+pub fn run_as_editor(args: &[String]) -> Option<i32> {
+    let flag = args.get(1)?;
+    if flag != SEQUENCE_EDITOR_FLAG && flag != MESSAGE_EDITOR_FLAG {
+        return None;
+    }
+
+    let result = match (args.get(2), std::env::var_os(PLAN_ENV)) {
+        (Some(file), Some(dir)) if flag == SEQUENCE_EDITOR_FLAG => {
+            write_todo(Path::new(&dir), Path::new(file))
+        }
+        (Some(file), Some(dir)) => write_message(Path::new(&dir), Path::new(file)),
+        _ => Err("Missing editor file or rebase plan".to_string()),
+    };
+
+    // Git reports a failing editor itself, so only the exit code matters.
+    Some(if result.is_ok() { 0 } else { 1 })
+}
+
+fn write_todo(dir: &Path, file: &Path) -> Result<(), String> {
+    std::fs::copy(dir.join("todo"), file)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to write rebase todo: {}", e))
+}
+
+/// Replaces the commit message git asks for with the one planned for the
+/// commit being picked, found as the last line of `rebase-merge/done`.
+/// Messages that were not planned are left as git prepared them.
+fn write_message(dir: &Path, file: &Path) -> Result<(), String> {
+    let Some(git_dir) = dir.parent().and_then(|d| d.parent()) else {
+        return Ok(());
+    };
+
+    let done =
+        std::fs::read_to_string(git_dir.join("rebase-merge").join("done")).unwrap_or_default();
+    let Some(hash) = done
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .and_then(|line| line.split_whitespace().nth(1))
+    else {
+        return Ok(());
+    };
+
+    let content = std::fs::read_to_string(dir.join("messages.json"))
+        .map_err(|e| format!("Failed to read rebase messages: {}", e))?;
+    let messages: HashMap<String, String> = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse rebase messages: {}", e))?;
+
+    match messages.get(hash) {
+        Some(message) => std::fs::write(file, format!("{}\n", message.trim_end()))
+            .map_err(|e| format!("Failed to write commit message: {}", e)),
+        None => Ok(()),
+    }
+}
+
+fn read_state_file(dir: &Path, name: &str) -> Option<String> {
+    std::fs::read_to_string(dir.join(name))
+        .ok()
+        .map(|content| content.trim().to_string())
+        .filter(|content| !content.is_empty())
+}
+
+/// Reads the progress of an interrupted rebase from `.git/rebase-merge`
+/// (or `.git/rebase-apply` for the apply backend).
+pub(crate) async fn read_rebase_progress(repo: &Path) -> Option<RebaseProgress> {
+    let git_dir = repo.join(".git");
+    let merge_dir = git_dir.join("rebase-merge");
+    let apply_dir = git_dir.join("rebase-apply");
+
+    let (dir, current_file, total_file) = if merge_dir.is_dir() {
+        (merge_dir, "msgnum", "end")
+    } else if apply_dir.is_dir() {
+        (apply_dir, "next", "last")
+    } else {
+        return None;
+    };
+
+    let current_step = read_state_file(&dir, current_file)
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(0);
+    let total_steps = read_state_file(&dir, total_file)
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(0);
+
+    let rebase_head = Command::new("git")
+        .arg("rev-parse")
+        .arg("--verify")
+        .arg("--quiet")
+        .arg("REBASE_HEAD")
+        .current_dir(repo)
+        .output()
+        .await
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+
+    let stopped_at = rebase_head.or_else(|| read_state_file(&dir, "stopped-sha"));
+
+    let stopped_subject = match &stopped_at {
+        Some(hash) => Command::new("git")
+            .arg("log")
+            .arg("-1")
+            .arg("--format=%s")
+            .arg(hash)
+            .current_dir(repo)
+            .output()
+            .await
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string()),
+        None => None,
+    };
+
+    Some(RebaseProgress {
+        current_step,
+        total_steps,
+        remaining: total_steps.saturating_sub(current_step),
+        stopped_at,
+        stopped_subject,
+        head_name: read_state_file(&dir, "head-name")
+            .map(|name| name.trim_start_matches("refs/heads/").to_string()),
+        onto: read_state_file(&dir, "onto"),
+    })
+}
+
+/// Turns the output of a rebase invocation into a `RebaseResult`, looking at
+/// the rebase state directory to tell a finished rebase from a stopped one.
+pub(crate) async fn rebase_outcome(repo: &Path, output: &Output) -> Result<RebaseResult, String> {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // Drop the "Rebasing (n/m)" progress git keeps rewriting with '\r'.
+    let message = format!("{}{}", stdout, stderr)
+        .lines()
+        .map(|line| {
+            line.rsplit('\r')
+                .next()
+                .unwrap_or(line)
+                .replace("\u{1b}[K", "")
+        })
+        .filter(|line| !line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let head = head_oid(repo).await;
+
+    if let Some(progress) = read_rebase_progress(repo).await {
+        let conflicts = conflicted_paths(repo).await?;
+        let status = if conflicts.is_empty() {
+            "stopped"
+        } else {
+            "conflict"
+        };
+
+        return Ok(RebaseResult {
+            status: status.to_string(),
+            head,
+            progress: Some(progress),
+            conflicts,
+            message,
+        });
+    }
+
+    clear_plan(repo)?;
+
+    if !output.status.success() {
+        discard_pending(repo, "rebase");
+        return Err(format!("Git rebase failed: {}", message));
+    }
+
+    finish_pending(repo, "rebase").await;
+
+    let status = if stdout.contains("is up to date") {
+        "up-to-date"
+    } else {
+        "finished"
+    };
+
+    Ok(RebaseResult {
+        status: status.to_string(),
+        head,
+        progress: None,
+        conflicts: Vec::new(),
+        message,
+    })
+}
+
+#[tauri::command]
+pub async fn rebase_onto(
+    repo_path: String,
+    upstream: String,
+    onto: Option<String>,
+    autostash: Option<bool>,
+) -> Result<RebaseResult, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    if read_rebase_progress(repo).await.is_some() {
+        return Err("A rebase is already in progress".to_string());
+    }
+
+    begin_pending(repo, "rebase").await;
+
+    let mut cmd = Command::new("git");
+    cmd.arg("rebase");
+
+    if autostash.unwrap_or(false) {
+        cmd.arg("--autostash");
+    } else {
+        cmd.arg("--no-autostash");
+    }
+
+    if let Some(onto) = onto.as_deref().filter(|o| !o.trim().is_empty()) {
+        cmd.arg(format!("--onto={}", onto.trim()));
+    }
+
+    let output = cmd
+        .arg("--end-of-options")
+        .arg(upstream.trim())
+        .env("LC_ALL", "C")
+        .env("GIT_EDITOR", "true")
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git rebase: {}", e))?;
+
+    rebase_outcome(repo, &output).await
+}
+
+async fn run_rebase_action(repo_path: &str, action: &str) -> Result<Output, String> {
+    let repo = Path::new(repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    if read_rebase_progress(repo).await.is_none() {
+        return Err("No rebase in progress".to_string());
+    }
+
+    let mut cmd = Command::new("git");
+    set_editor_env(&mut cmd, repo)?;
+
+    cmd.arg("rebase")
+        .arg(action)
+        .env("LC_ALL", "C")
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git rebase {}: {}", action, e))
+}
+
+#[tauri::command]
+pub async fn rebase_continue(repo_path: String) -> Result<RebaseResult, String> {
+    let output = run_rebase_action(&repo_path, "--continue").await?;
+    rebase_outcome(Path::new(&repo_path), &output).await
+}
+
+#[tauri::command]
+pub async fn rebase_skip(repo_path: String) -> Result<RebaseResult, String> {
+    let output = run_rebase_action(&repo_path, "--skip").await?;
+    rebase_outcome(Path::new(&repo_path), &output).await
+}
+
+#[tauri::command]
+pub async fn rebase_abort(repo_path: String) -> Result<(), String> {
+    let output = run_rebase_action(&repo_path, "--abort").await?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git rebase --abort failed: {}", stderr));
+    }
+
+    clear_plan(Path::new(&repo_path))?;
+    discard_pending(Path::new(&repo_path), "rebase");
+    Ok(())
+}
+
+/// The todo list handed to git and the messages planned for the commits
+/// that end a `reword` or `squash`.
+struct RebasePlan {
+    lines: Vec<String>,
+    messages: HashMap<String, String>,
+}
+
+/// Matches the todo entries from the UI against `commits`, the full hashes
+/// being rebased, and checks that every commit is planned exactly once.
+fn plan_rebase(commits: &[String], todo: &[RebaseTodoEntry]) -> Result<RebasePlan, String> {
+    let mut lines = Vec::new();
+    let mut messages: HashMap<String, String> = HashMap::new();
+    let mut planned: Vec<&String> = Vec::new();
+    // Last commit of the current pick/squash chain, whether it contains a
+    // squash, and the message planned for it.
+    let mut chain: Option<(String, bool, Option<String>)> = None;
+
+    for entry in todo {
+        let short = entry.hash.trim();
+        let matches: Vec<&String> = commits
+            .iter()
+            .filter(|hash| !short.is_empty() && hash.starts_with(short))
+            .collect();
+
+        let hash = match matches.as_slice() {
+            [hash] => *hash,
+            [] => return Err(format!("Commit {} is not part of the rebase range", short)),
+            _ => return Err(format!("Commit {} is ambiguous", short)),
+        };
+
+        if planned.contains(&hash) {
+            return Err(format!("Commit {} appears more than once", short));
+        }
+        planned.push(hash);
+
+        let message = entry
+            .message
+            .as_deref()
+            .filter(|m| !m.trim().is_empty())
+            .map(|m| m.to_string());
+
+        match entry.action.as_str() {
+            "drop" => {}
+            "squash" | "fixup" => {
+                let Some(current) = chain.as_mut() else {
+                    return Err(format!(
+                        "Cannot {} {} without a previous commit",
+                        entry.action, short
+                    ));
+                };
+                current.0 = hash.clone();
+                if entry.action == "squash" {
+                    current.1 = true;
+                    if message.is_some() {
+                        current.2 = message;
+                    }
+                }
+            }
+            "pick" | "reword" | "edit" => {
+                if let Some((last, true, Some(message))) = chain.take() {
+                    messages.insert(last, message);
+                }
+                if entry.action == "reword" {
+                    if let Some(message) = message {
+                        messages.insert(hash.clone(), message);
+                    }
+                }
+                chain = Some((hash.clone(), false, None));
+            }
+            other => return Err(format!("Unsupported rebase action '{}'", other)),
+        }
+
+        lines.push(format!("{} {}", entry.action, hash));
+    }
+
+    if let Some((last, true, Some(message))) = chain.take() {
+        messages.insert(last, message);
+    }
+
+    if let Some(missing) = commits.iter().find(|hash| !planned.contains(hash)) {
+        return Err(format!(
+            "The todo list does not mention commit {}",
+            &missing[..missing.len().min(7)]
+        ));
+    }
+
+    Ok(RebasePlan { lines, messages })
+}
+
+/// Runs `git rebase -i` with the todo list planned in the UI. Messages are
+/// used for `reword` entries and, for a chain of `squash`/`fixup` entries,
+/// the last message given on a `squash` becomes the combined message.
+#[tauri::command]
+pub async fn interactive_rebase(
+    repo_path: String,
+    base: Option<String>,
+    todo: Vec<RebaseTodoEntry>,
+    autostash: Option<bool>,
+) -> Result<RebaseResult, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    if read_rebase_progress(repo).await.is_some() {
+        return Err("A rebase is already in progress".to_string());
+    }
+
+    if todo.is_empty() {
+        return Err("The rebase todo list is empty".to_string());
+    }
+
+    let base = base.map(|b| b.trim().to_string()).filter(|b| !b.is_empty());
+
+    let range = match &base {
+        Some(base) => format!("{}..HEAD", base),
+        None => "HEAD".to_string(),
+    };
+
+    let rev_list = Command::new("git")
+        .arg("rev-list")
+        .arg("--reverse")
+        .arg("--no-merges")
+        .arg("--end-of-options")
+        .arg(&range)
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git rev-list: {}", e))?;
+
+    if !rev_list.status.success() {
+        let stderr = String::from_utf8_lossy(&rev_list.stderr);
+        return Err(format!("Git rev-list failed: {}", stderr));
+    }
+
+    let commits: Vec<String> = String::from_utf8_lossy(&rev_list.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    let plan = plan_rebase(&commits, &todo)?;
+
+    let dir = plan_dir(repo);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create rebase plan directory: {}", e))?;
+    std::fs::write(dir.join("todo"), format!("{}\n", plan.lines.join("\n")))
+        .map_err(|e| format!("Failed to write rebase todo: {}", e))?;
+    let messages = serde_json::to_string_pretty(&plan.messages)
+        .map_err(|e| format!("Failed to serialize rebase messages: {}", e))?;
+    std::fs::write(dir.join("messages.json"), messages)
+        .map_err(|e| format!("Failed to write rebase messages: {}", e))?;
+
+    begin_pending(repo, "rebase").await;
+
+    let mut cmd = Command::new("git");
+    set_editor_env(&mut cmd, repo)?;
+    cmd.arg("rebase")
+        .arg("--interactive")
+        .arg("--no-autosquash");
+
+    if autostash.unwrap_or(false) {
+        cmd.arg("--autostash");
+    } else {
+        cmd.arg("--no-autostash");
+    }
+
+    match &base {
+        Some(base) => cmd.arg("--end-of-options").arg(base),
+        None => cmd.arg("--root"),
+    };
+
+    let output = cmd
+        .env("LC_ALL", "C")
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git rebase: {}", e))?;
+
+    rebase_outcome(repo, &output).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_util::{commit_file, git, init_repo, path_str, write_file};
+    use tempfile::TempDir;
+
+    pub(super) const EDITOR_TEST: &str = "commands::rebase::tests::acts_as_editor";
+
+    /// A repository on `feature`, which edits `a.txt` and then adds `b.txt`
+    /// on top of `base`. `main` edits `a.txt` as well when `conflict` is set
+    /// and adds `c.txt` otherwise.
+    fn diverged_repo(conflict: bool) -> TempDir {
+        let repo = init_repo();
+        commit_file(repo.path(), "a.txt", "base\n", "base");
+        git(repo.path(), &["switch", "-q", "-c", "feature"]);
+        commit_file(repo.path(), "a.txt", "feature\n", "feature edit");
+        commit_file(repo.path(), "b.txt", "b\n", "feature add");
+        git(repo.path(), &["switch", "-q", "main"]);
+        if conflict {
+            commit_file(repo.path(), "a.txt", "main\n", "main edit");
+        } else {
+            commit_file(repo.path(), "c.txt", "c\n", "main add");
+        }
+        git(repo.path(), &["switch", "-q", "feature"]);
+        repo
+    }
+
+    async fn rebase_on_main(repo: &TempDir) -> RebaseResult {
+        rebase_onto(path_str(repo), "main".to_string(), None, None)
+            .await
+            .unwrap_or_else(|e| panic!("rebase failed: {}", e))
+    }
+
+    fn subjects(repo: &TempDir) -> String {
+        git(repo.path(), &["log", "--format=%s", "main~1..HEAD"])
+    }
+
+    #[tokio::test]
+    async fn rebases_onto_the_upstream() {
+        let repo = diverged_repo(false);
+
+        let result = rebase_on_main(&repo).await;
+
+        assert_eq!(result.status, "finished");
+        assert!(result.progress.is_none());
+        assert_eq!(subjects(&repo), "feature add\nfeature edit\nmain add");
+        assert_eq!(result.head, Some(git(repo.path(), &["rev-parse", "HEAD"])));
+    }
+
+    #[tokio::test]
+    async fn continues_after_a_resolved_conflict() {
+        let repo = diverged_repo(true);
+
+        let result = rebase_on_main(&repo).await;
+
+        assert_eq!(result.status, "conflict");
+        assert_eq!(result.conflicts, ["a.txt"]);
+        let progress = result.progress.expect("rebase should be in progress");
+        assert_eq!((progress.current_step, progress.total_steps), (1, 2));
+        assert_eq!(progress.head_name.as_deref(), Some("feature"));
+        assert_eq!(progress.stopped_subject.as_deref(), Some("feature edit"));
+
+        write_file(repo.path(), "a.txt", "resolved\n");
+        git(repo.path(), &["add", "a.txt"]);
+        let result = rebase_continue(path_str(&repo)).await.unwrap();
+
+        assert_eq!(result.status, "finished");
+        assert_eq!(subjects(&repo), "feature add\nfeature edit\nmain edit");
+        assert_eq!(git(repo.path(), &["show", "HEAD:a.txt"]), "resolved");
+    }
+
+    #[tokio::test]
+    async fn skips_the_conflicting_commit() {
+        let repo = diverged_repo(true);
+        rebase_on_main(&repo).await;
+
+        let result = rebase_skip(path_str(&repo)).await.unwrap();
+
+        assert_eq!(result.status, "finished");
+        assert_eq!(subjects(&repo), "feature add\nmain edit");
+    }
+
+    #[tokio::test]
+    async fn abort_restores_the_branch() {
+        let repo = diverged_repo(true);
+        let head = git(repo.path(), &["rev-parse", "HEAD"]);
+        rebase_on_main(&repo).await;
+
+        rebase_abort(path_str(&repo)).await.unwrap();
+
+        assert_eq!(git(repo.path(), &["rev-parse", "HEAD"]), head);
+        assert!(read_rebase_progress(repo.path()).await.is_none());
+        assert!(matches!(
+            rebase_abort(path_str(&repo)).await,
+            Err(e) if e == "No rebase in progress"
+        ));
+    }
+
+    #[tokio::test]
+    async fn reads_the_apply_backend_progress() {
+        let repo = diverged_repo(true);
+        let onto = git(repo.path(), &["rev-parse", "main"]);
+        let status = std::process::Command::new("git")
+            .args(["rebase", "--apply", "main"])
+            .current_dir(repo.path())
+            .output()
+            .expect("failed to run git")
+            .status;
+        assert!(!status.success());
+        assert!(repo.path().join(".git/rebase-apply").is_dir());
+
+        let progress = read_rebase_progress(repo.path()).await.unwrap();
+
+        assert_eq!((progress.current_step, progress.total_steps), (1, 2));
+        assert_eq!(progress.remaining, 1);
+        assert_eq!(progress.head_name.as_deref(), Some("feature"));
+        assert_eq!(progress.onto.as_deref(), Some(onto.as_str()));
+        assert_eq!(progress.stopped_subject.as_deref(), Some("feature edit"));
+    }
+
+    #[tokio::test]
+    async fn reads_the_merge_backend_progress() {
+        let repo = diverged_repo(true);
+        let onto = git(repo.path(), &["rev-parse", "main"]);
+        let stopped = git(repo.path(), &["rev-parse", "feature~1"]);
+        rebase_on_main(&repo).await;
+        assert!(repo.path().join(".git/rebase-merge").is_dir());
+
+        let progress = read_rebase_progress(repo.path()).await.unwrap();
+
+        assert_eq!((progress.current_step, progress.total_steps), (1, 2));
+        assert_eq!(progress.remaining, 1);
+        assert_eq!(progress.onto.as_deref(), Some(onto.as_str()));
+        assert_eq!(progress.stopped_at.as_deref(), Some(stopped.as_str()));
+    }
+
+    #[tokio::test]
+    async fn upstream_is_never_parsed_as_an_option() {
+        let repo = diverged_repo(false);
+        let head = git(repo.path(), &["rev-parse", "HEAD"]);
+
+        let result = rebase_onto(path_str(&repo), "--root".to_string(), None, None).await;
+
+        assert!(matches!(result, Err(e) if e.contains("invalid upstream '--root'")));
+        assert_eq!(git(repo.path(), &["rev-parse", "HEAD"]), head);
+    }
+
+    /// Runs as git's sequence or message editor when `editor_command` starts
+    /// the test harness, and does nothing in a normal test run.
+    #[test]
+    fn acts_as_editor() {
+        let args: Vec<String> = std::env::args().collect();
+        let Some(flag) = args
+            .iter()
+            .position(|arg| arg == SEQUENCE_EDITOR_FLAG || arg == MESSAGE_EDITOR_FLAG)
+        else {
+            return;
+        };
+
+        assert_eq!(run_as_editor(&args[flag - 1..]), Some(0));
+    }
+
+    fn entry(action: &str, hash: &str, message: Option<&str>) -> RebaseTodoEntry {
+        RebaseTodoEntry {
+            action: action.to_string(),
+            hash: hash.to_string(),
+            message: message.map(str::to_string),
+        }
+    }
+
+    fn hashes() -> Vec<String> {
+        ["aaaa1111", "bbbb2222", "cccc3333", "dddd4444"]
+            .iter()
+            .map(|h| h.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn plans_a_reordered_todo_list_with_drops() {
+        let todo = [
+            entry("pick", "cccc", None),
+            entry("pick", "aaaa", Some("ignored for a pick")),
+            entry("drop", "dddd", None),
+            entry("pick", "bbbb", None),
+        ];
+
+        let plan = plan_rebase(&hashes(), &todo).unwrap_or_else(|e| panic!("{}", e));
+
+        assert_eq!(
+            plan.lines,
+            [
+                "pick cccc3333",
+                "pick aaaa1111",
+                "drop dddd4444",
+                "pick bbbb2222"
+            ]
+        );
+        assert!(plan.messages.is_empty());
+    }
+
+    #[test]
+    fn plans_squash_and_fixup_messages_on_the_last_commit_of_the_chain() {
+        let todo = [
+            entry("pick", "aaaa", None),
+            entry("squash", "bbbb", Some("combined")),
+            entry("fixup", "cccc", None),
+            entry("reword", "dddd", Some("reworded")),
+        ];
+
+        let plan = plan_rebase(&hashes(), &todo).unwrap_or_else(|e| panic!("{}", e));
+
+        assert_eq!(
+            plan.lines,
+            [
+                "pick aaaa1111",
+                "squash bbbb2222",
+                "fixup cccc3333",
+                "reword dddd4444"
+            ]
+        );
+        assert_eq!(plan.messages.len(), 2);
+        assert_eq!(plan.messages["cccc3333"], "combined");
+        assert_eq!(plan.messages["dddd4444"], "reworded");
+    }
+
+    #[test]
+    fn fixup_chains_keep_the_message_git_prepares() {
+        let todo = [
+            entry("pick", "aaaa", None),
+            entry("fixup", "bbbb", Some("only squash messages count")),
+            entry("pick", "cccc", None),
+            entry("reword", "dddd", None),
+        ];
+
+        let plan = plan_rebase(&hashes(), &todo).unwrap_or_else(|e| panic!("{}", e));
+
+        assert!(plan.messages.is_empty());
+    }
+
+    #[test]
+    fn rejects_todo_lists_that_do_not_cover_the_range() {
+        let error = |todo: &[RebaseTodoEntry]| match plan_rebase(&hashes(), todo) {
+            Ok(_) => panic!("plan should be rejected"),
+            Err(e) => e,
+        };
+        let all = |first: RebaseTodoEntry| {
+            [
+                first,
+                entry("pick", "bbbb", None),
+                entry("pick", "cccc", None),
+                entry("pick", "dddd", None),
+            ]
+        };
+
+        assert_eq!(
+            error(&all(entry("squash", "aaaa", None))),
+            "Cannot squash aaaa without a previous commit"
+        );
+        assert_eq!(
+            error(&all(entry("exec", "aaaa", None))),
+            "Unsupported rebase action 'exec'"
+        );
+        assert_eq!(
+            error(&all(entry("pick", "eeee", None))),
+            "Commit eeee is not part of the rebase range"
+        );
+        assert_eq!(
+            error(&all(entry("pick", "bbbb", None))),
+            "Commit bbbb appears more than once"
+        );
+        assert_eq!(
+            error(&all(entry("pick", "aaaa", None))[1..]),
+            "The todo list does not mention commit aaaa111"
+        );
+    }
+
+    #[tokio::test]
+    async fn interactive_rebase_runs_the_plan_through_the_editors() {
+        let repo = init_repo();
+        let base = commit_file(repo.path(), "a.txt", "base\n", "base");
+        let one = commit_file(repo.path(), "a.txt", "one\n", "one");
+        let two = commit_file(repo.path(), "b.txt", "two\n", "two");
+        let three = commit_file(repo.path(), "c.txt", "three\n", "three");
+        let four = commit_file(repo.path(), "d.txt", "four\n", "four");
+
+        let todo = vec![
+            entry("pick", &three[..8], None),
+            entry("reword", &one[..8], Some("first, reworded")),
+            entry("squash", &two[..8], Some("second and third")),
+            entry("drop", &four[..8], None),
+        ];
+        let result = interactive_rebase(path_str(&repo), Some(base), todo, None)
+            .await
+            .unwrap_or_else(|e| panic!("rebase failed: {}", e));
+
+        assert_eq!(result.status, "finished", "{}", result.message);
+        assert_eq!(
+            git(repo.path(), &["log", "--format=%s", "HEAD~2..HEAD"]),
+            "second and third\nthree"
+        );
+        assert_eq!(git(repo.path(), &["show", "HEAD:b.txt"]), "two");
+        assert!(!repo.path().join("d.txt").exists());
+        assert!(!plan_dir(repo.path()).exists());
+    }
+}
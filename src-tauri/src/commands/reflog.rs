@@ -0,0 +1,233 @@
+use std::path::Path;
+
+use super::git::{create_git_branch, run_git};
+use super::reset::reset_to_commit;
+
+#[derive(serde::Serialize)]
+pub struct ReflogEntry {
+    pub selector: String,
+    /// `git log -g` has no placeholder for the value an entry replaced, so
+    /// this is `None` whenever git does not report it.
+    pub old_oid: Option<String>,
+    pub new_oid: String,
+    pub action: String,
+    pub message: String,
+    pub committer_name: String,
+    pub committer_email: String,
+    pub timestamp: i64,
+    pub timezone: String,
+}
+
+/// One reflog entry per record with `-z`: selector, new oid, identity and
+/// subject (`<action>: <message>`). With `--date=raw` the selector carries
+/// the entry's time instead of its index, e.g. `HEAD@{1700000000 +0100}`.
+const REFLOG_FORMAT: &str = "--format=%gd%x1F%H%x1F%gn%x1F%ge%x1F%gs";
+
+/// Parses a `REFLOG_FORMAT` record printed at position `index` of the
+/// reflog, which becomes the `@{index}` of its selector.
+fn parse_reflog_record(record: &str, index: usize) -> Option<ReflogEntry> {
+    let fields: Vec<&str> = record.split('\u{001F}').collect();
+    if fields.len() < 5 {
+        return None;
+    }
+
+    let (name, date) = fields[0].rsplit_once("@{")?;
+    let (timestamp, timezone) = date.strip_suffix('}')?.split_once(' ')?;
+
+    let (action, message) = match fields[4].split_once(": ") {
+        Some((action, message)) => (action.to_string(), message.to_string()),
+        None => (fields[4].to_string(), String::new()),
+    };
+
+    Some(ReflogEntry {
+        selector: format!("{}@{{{}}}", name, index),
+        old_oid: None,
+        new_oid: fields[1].to_string(),
+        action,
+        message,
+        committer_name: fields[2].to_string(),
+        committer_email: fields[3].to_string(),
+        timestamp: timestamp.parse().ok()?,
+        timezone: timezone.to_string(),
+    })
+}
+
+#[tauri::command]
+pub async fn get_reflog(
+    repo_path: String,
+    reference: Option<String>,
+    skip: Option<u32>,
+    limit: Option<u32>,
+) -> Result<Vec<ReflogEntry>, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let reference = reference
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .unwrap_or_else(|| "HEAD".to_string());
+
+    let resolved = run_git(
+        repo,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            "--end-of-options",
+            &reference,
+        ],
+    )
+    .await;
+
+    if resolved.is_err() {
+        // HEAD and the branch it points to have no reflog until the first commit.
+        let unborn = run_git(repo, &["symbolic-ref", "--quiet", "HEAD"])
+            .await
+            .ok();
+        let is_unborn = reference == "HEAD"
+            || unborn.as_deref().is_some_and(|head| {
+                head == reference || head.strip_prefix("refs/heads/") == Some(reference.as_str())
+            });
+
+        if is_unborn {
+            return Ok(Vec::new());
+        }
+        return Err(format!("Reference '{}' does not exist", reference));
+    }
+
+    let skip = skip.unwrap_or(0);
+    let skip_arg = format!("--skip={}", skip);
+    let count_arg = limit.map(|l| format!("--max-count={}", l));
+
+    let mut args = vec!["log", "-g", "-z", "--date=raw", &skip_arg];
+    if let Some(count_arg) = &count_arg {
+        args.push(count_arg);
+    }
+    args.extend([REFLOG_FORMAT, "--end-of-options", &reference, "--"]);
+
+    let stdout = run_git(repo, &args).await?;
+
+    // Records are numbered before parsing, so one that fails to parse does
+    // not shift the selectors after it.
+    Ok(stdout
+        .split('\0')
+        .filter(|record| !record.is_empty())
+        .enumerate()
+        .filter_map(|(index, record)| parse_reflog_record(record, skip as usize + index))
+        .collect())
+}
+
+/// Brings back the commit a reflog entry points to, either as a new branch
+/// or by resetting the current branch to it (`keep` mode unless given).
+#[tauri::command]
+pub async fn restore_reflog_entry(
+    repo_path: String,
+    oid: String,
+    branch_name: Option<String>,
+    mode: Option<String>,
+) -> Result<(), String> {
+    match branch_name.filter(|b| !b.trim().is_empty()) {
+        Some(branch_name) => {
+            create_git_branch(repo_path, branch_name, Some(oid), Some(false)).await
+        }
+        None => {
+            let mode = mode.unwrap_or_else(|| "keep".to_string());
+            reset_to_commit(repo_path, oid, mode).await.map(|_| ())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_util::{commit_file, git, init_repo, path_str};
+
+    #[test]
+    fn parses_reflog_records() {
+        let record = "main@{1792314276 +0200}\u{1F}921e046b03e1ba2640d165651f2cc5795d6c96de\u{1F}\
+                      A U Thor\u{1F}author@example.com\u{1F}commit (amend): Fix: the thing";
+        let entry = parse_reflog_record(record, 2).unwrap();
+        assert_eq!(entry.selector, "main@{2}");
+        assert_eq!(entry.old_oid, None);
+        assert_eq!(entry.new_oid, "921e046b03e1ba2640d165651f2cc5795d6c96de");
+        assert_eq!(entry.committer_name, "A U Thor");
+        assert_eq!(entry.committer_email, "author@example.com");
+        assert_eq!(entry.action, "commit (amend)");
+        assert_eq!(entry.message, "Fix: the thing");
+        assert_eq!(
+            (entry.timestamp, entry.timezone.as_str()),
+            (1792314276, "+0200")
+        );
+
+        let record = "refs/heads/a@b@{1 -0130}\u{1F}abc\u{1F}\u{1F}\u{1F}reset";
+        let entry = parse_reflog_record(record, 0).unwrap();
+        assert_eq!(entry.selector, "refs/heads/a@b@{0}");
+        assert_eq!(entry.action, "reset");
+        assert_eq!(entry.message, "");
+        assert_eq!((entry.timestamp, entry.timezone.as_str()), (1, "-0130"));
+
+        assert!(parse_reflog_record("HEAD@{0}\u{1F}abc\u{1F}\u{1F}\u{1F}reset", 0).is_none());
+        assert!(parse_reflog_record("HEAD@{1 +0000}\u{1F}abc", 0).is_none());
+    }
+
+    #[tokio::test]
+    async fn lists_reflog_pages_with_selectors_and_dates() {
+        let repo = init_repo();
+        let first = commit_file(repo.path(), "a.txt", "1\n", "first");
+        let second = commit_file(repo.path(), "a.txt", "2\n", "second");
+        let third = commit_file(repo.path(), "a.txt", "3\n", "third");
+
+        let all = get_reflog(path_str(&repo), None, None, None).await.unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].selector, "HEAD@{0}");
+        assert_eq!(all[0].new_oid, third);
+        assert_eq!(all[1].new_oid, second);
+        assert_eq!(all[0].action, "commit");
+        assert_eq!(all[0].message, "third");
+        assert_eq!(all[2].action, "commit (initial)");
+        assert_eq!(all[2].new_oid, first);
+        assert!(all.iter().all(|entry| entry.old_oid.is_none()));
+        assert!(all[0].timestamp > 0);
+
+        let page = get_reflog(path_str(&repo), Some("main".to_string()), Some(1), Some(1))
+            .await
+            .unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].selector, "main@{1}");
+        assert_eq!(page[0].new_oid, second);
+        assert_eq!(
+            git(repo.path(), &["rev-parse", &page[0].selector]),
+            page[0].new_oid
+        );
+    }
+
+    #[tokio::test]
+    async fn handles_unborn_and_missing_refs() {
+        let repo = init_repo();
+
+        assert!(get_reflog(path_str(&repo), None, None, None)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(
+            get_reflog(path_str(&repo), Some("main".to_string()), None, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        commit_file(repo.path(), "a.txt", "1\n", "first");
+        let missing = get_reflog(path_str(&repo), Some("nope".to_string()), None, None).await;
+        assert!(missing.is_err());
+        let option = get_reflog(path_str(&repo), Some("--all".to_string()), None, None).await;
+        assert!(option.is_err());
+    }
+}
@@ -0,0 +1,374 @@
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::git::run_git;
+use super::journal::{capture_state, record_operation};
+use super::merge::head_oid;
+use super::stash::parse_stash_subject;
+
+const SNAPSHOT_REF_PREFIX: &str = "refs/git-client/snapshots/";
+
+/// Trailer recording the branch HEAD pointed at when a snapshot was taken.
+const SNAPSHOT_BRANCH_TRAILER: &str = "Snapshot-Branch";
+
+#[derive(serde::Serialize)]
+pub struct ResetSnapshot {
+    pub reference: String,
+    pub hash: String,
+    pub head: String,
+    pub branch: Option<String>,
+    pub message: String,
+    pub has_changes: bool,
+    pub timestamp: i64,
+}
+
+#[derive(serde::Serialize)]
+pub struct ResetResult {
+    pub head: Option<String>,
+    pub snapshot: Option<ResetSnapshot>,
+}
+
+async fn current_branch(repo: &Path) -> Option<String> {
+    run_git(repo, &["symbolic-ref", "--quiet", "HEAD"])
+        .await
+        .ok()
+}
+
+/// Records HEAD and any uncommitted tracked changes under
+/// `refs/git-client/snapshots/`. With changes the snapshot is a stash commit
+/// (HEAD and the index as parents); without, a commit of HEAD's tree whose
+/// only parent is HEAD. The checked-out branch, if any, is kept in a
+/// `Snapshot-Branch` trailer.
+pub(crate) async fn create_snapshot(repo: &Path, reason: &str) -> Result<ResetSnapshot, String> {
+    let head = head_oid(repo)
+        .await
+        .ok_or_else(|| "Cannot snapshot a repository without commits".to_string())?;
+    let branch = current_branch(repo).await;
+    let message = format!(
+        "Snapshot of {} before {}",
+        branch
+            .as_deref()
+            .map(|b| b.strip_prefix("refs/heads/").unwrap_or(b))
+            .unwrap_or("detached HEAD"),
+        reason
+    );
+    let full_message = match &branch {
+        Some(branch) => format!("{}\n\n{}: {}", message, SNAPSHOT_BRANCH_TRAILER, branch),
+        None => message.clone(),
+    };
+
+    let stash = run_git(repo, &["stash", "create", &full_message]).await?;
+    let has_changes = !stash.is_empty();
+
+    let hash = if has_changes {
+        stash
+    } else {
+        let tree = format!("{}^{{tree}}", head);
+        run_git(
+            repo,
+            &["commit-tree", &tree, "-p", &head, "-m", &full_message],
+        )
+        .await?
+    };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| e.to_string())?;
+    let reference = format!(
+        "{}{}-{:09}",
+        SNAPSHOT_REF_PREFIX,
+        now.as_secs(),
+        now.subsec_nanos()
+    );
+
+    run_git(repo, &["update-ref", "-m", &message, &reference, &hash]).await?;
+
+    Ok(ResetSnapshot {
+        reference,
+        hash,
+        head,
+        branch,
+        message,
+        has_changes,
+        timestamp: now.as_secs() as i64,
+    })
+}
+
+#[tauri::command]
+pub async fn reset_to_commit(
+    repo_path: String,
+    commit: String,
+    mode: String,
+) -> Result<ResetResult, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    if !matches!(mode.as_str(), "soft" | "mixed" | "hard" | "keep") {
+        return Err(format!("Unsupported reset mode '{}'", mode));
+    }
+
+    let target = run_git(
+        repo,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", commit.trim()),
+        ],
+    )
+    .await
+    .map_err(|_| format!("Commit '{}' does not exist", commit.trim()))?;
+
+    let snapshot = if mode == "hard" {
+        let reason = format!("reset --hard to {}", &target[..target.len().min(7)]);
+        Some(create_snapshot(repo, &reason).await?)
+    } else {
+        None
+    };
+
+    let before = capture_state(repo).await;
+    run_git(repo, &["reset", &format!("--{}", mode), &target]).await?;
+    record_operation(
+        repo,
+        &format!("reset --{}", mode),
+        matches!(mode.as_str(), "hard" | "keep"),
+        before,
+    )
+    .await;
+
+    Ok(ResetResult {
+        head: head_oid(repo).await,
+        snapshot,
+    })
+}
+
+#[tauri::command]
+pub async fn list_reset_snapshots(repo_path: String) -> Result<Vec<ResetSnapshot>, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let format = format!(
+        "--format=%(refname)%1E%(objectname)%1E%(parent)%1E%(subject)%1E%(creatordate:unix)\
+         %1E%(trailers:key={},valueonly,separator=)",
+        SNAPSHOT_BRANCH_TRAILER
+    );
+    let stdout = run_git(
+        repo,
+        &[
+            "for-each-ref",
+            "--sort=-refname",
+            &format,
+            SNAPSHOT_REF_PREFIX,
+        ],
+    )
+    .await?;
+
+    let mut snapshots = Vec::new();
+
+    for line in stdout.lines() {
+        let parts: Vec<&str> = line.split('\u{001E}').collect();
+        if parts.len() < 6 {
+            continue;
+        }
+
+        let parents: Vec<&str> = parts[2].split_whitespace().collect();
+        let Some(head) = parents.first() else {
+            continue;
+        };
+
+        snapshots.push(ResetSnapshot {
+            reference: parts[0].to_string(),
+            hash: parts[1].to_string(),
+            head: head.to_string(),
+            branch: Some(parts[5].trim())
+                .filter(|b| !b.is_empty())
+                .map(str::to_string),
+            message: parse_stash_subject(parts[3]).1,
+            has_changes: parents.len() > 1,
+            timestamp: parts[4].parse().unwrap_or(0),
+        });
+    }
+
+    Ok(snapshots)
+}
+
+/// Resets the branch the snapshot was taken on back to the snapshot's HEAD and
+/// re-applies the uncommitted changes it captured. That branch (or a detached
+/// HEAD) must be checked out. The state being replaced is snapshotted first
+/// so the restore itself can be undone.
+#[tauri::command]
+pub async fn restore_reset_snapshot(
+    repo_path: String,
+    reference: String,
+) -> Result<ResetResult, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    if !reference.starts_with(SNAPSHOT_REF_PREFIX) {
+        return Err("Invalid snapshot reference".to_string());
+    }
+
+    let snapshot = list_reset_snapshots(repo_path.clone())
+        .await?
+        .into_iter()
+        .find(|s| s.reference == reference)
+        .ok_or_else(|| format!("Snapshot '{}' not found", reference))?;
+
+    if current_branch(repo).await != snapshot.branch {
+        return Err(match &snapshot.branch {
+            Some(branch) => format!(
+                "The snapshot was taken on {}, check it out before restoring",
+                branch.strip_prefix("refs/heads/").unwrap_or(branch)
+            ),
+            None => "The snapshot was taken on a detached HEAD, detach HEAD before restoring"
+                .to_string(),
+        });
+    }
+
+    let current = create_snapshot(repo, "snapshot restore").await?;
+    let before = capture_state(repo).await;
+
+    run_git(repo, &["reset", "--hard", &snapshot.head]).await?;
+
+    // The reset has already happened, so journal it even if re-applying the
+    // changes fails.
+    let applied = if snapshot.has_changes {
+        run_git(repo, &["stash", "apply", "--index", &snapshot.hash])
+            .await
+            .map(|_| ())
+    } else {
+        Ok(())
+    };
+
+    record_operation(repo, "restore snapshot", true, before).await;
+    applied?;
+
+    Ok(ResetResult {
+        head: head_oid(repo).await,
+        snapshot: Some(current),
+    })
+}
+
+#[tauri::command]
+pub async fn delete_reset_snapshot(repo_path: String, reference: String) -> Result<(), String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    if !reference.starts_with(SNAPSHOT_REF_PREFIX) {
+        return Err("Invalid snapshot reference".to_string());
+    }
+
+    run_git(repo, &["update-ref", "-d", &reference]).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::journal::list_operations;
+    use crate::commands::test_util::{commit_file, git, init_repo, path_str, write_file};
+
+    #[tokio::test]
+    async fn restores_snapshot_on_its_branch() {
+        let repo = init_repo();
+        let first = commit_file(repo.path(), "a.txt", "one\n", "first");
+        commit_file(repo.path(), "a.txt", "two\n", "second");
+        write_file(repo.path(), "a.txt", "local\n");
+
+        let result = reset_to_commit(path_str(&repo), first.clone(), "hard".to_string())
+            .await
+            .unwrap();
+        let snapshot = result.snapshot.unwrap();
+        assert_eq!(snapshot.branch.as_deref(), Some("refs/heads/main"));
+        assert!(snapshot.has_changes);
+
+        let listed = list_reset_snapshots(path_str(&repo)).await.unwrap();
+        assert_eq!(listed[0].branch.as_deref(), Some("refs/heads/main"));
+        assert_eq!(listed[0].message, snapshot.message);
+
+        restore_reset_snapshot(path_str(&repo), snapshot.reference)
+            .await
+            .unwrap();
+        assert_eq!(git(repo.path(), &["rev-parse", "HEAD"]), snapshot.head);
+        assert_eq!(
+            std::fs::read_to_string(repo.path().join("a.txt")).unwrap(),
+            "local\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn refuses_to_restore_onto_another_branch() {
+        let repo = init_repo();
+        let first = commit_file(repo.path(), "a.txt", "one\n", "first");
+        commit_file(repo.path(), "a.txt", "two\n", "second");
+
+        let snapshot = reset_to_commit(path_str(&repo), first.clone(), "hard".to_string())
+            .await
+            .unwrap()
+            .snapshot
+            .unwrap();
+
+        git(repo.path(), &["switch", "-q", "-c", "other"]);
+        let result = restore_reset_snapshot(path_str(&repo), snapshot.reference.clone()).await;
+        assert!(matches!(result, Err(e) if e.contains("taken on main")));
+        assert_eq!(git(repo.path(), &["rev-parse", "other"]), first);
+
+        git(repo.path(), &["switch", "-q", "--detach"]);
+        let result = restore_reset_snapshot(path_str(&repo), snapshot.reference).await;
+        assert!(result.is_err());
+        assert_eq!(git(repo.path(), &["rev-parse", "HEAD"]), first);
+    }
+
+    #[tokio::test]
+    async fn journals_reset_when_reapplying_changes_fails() {
+        let repo = init_repo();
+        let first = commit_file(repo.path(), "a.txt", "one\n", "first");
+        write_file(repo.path(), "new.txt", "staged\n");
+        git(repo.path(), &["add", "new.txt"]);
+
+        let snapshot = reset_to_commit(path_str(&repo), first, "hard".to_string())
+            .await
+            .unwrap()
+            .snapshot
+            .unwrap();
+        let second = commit_file(repo.path(), "a.txt", "two\n", "second");
+
+        // An untracked file in the way makes `stash apply` fail after the reset.
+        write_file(repo.path(), "new.txt", "untracked\n");
+
+        let result = restore_reset_snapshot(path_str(&repo), snapshot.reference).await;
+        assert!(result.is_err());
+        assert_eq!(git(repo.path(), &["rev-parse", "HEAD"]), snapshot.head);
+
+        let operations = list_operations(path_str(&repo)).unwrap();
+        assert_eq!(operations[0].operation, "restore snapshot");
+        assert_eq!(operations[0].before.head.as_deref(), Some(second.as_str()));
+    }
+}
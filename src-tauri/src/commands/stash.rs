@@ -0,0 +1,375 @@
+use std::path::Path;
+use tokio::process::Command;
+
+use super::diff::{parse_unified_diff, FileDiff, PATCH_FORMAT_ARGS};
+use super::git::{parse_raw_numstat, ChangedFile};
+use super::journal::{capture_state, record_operation};
+
+#[derive(serde::Serialize)]
+pub struct StashEntry {
+    pub index: u32,
+    pub reference: String,
+    pub hash: String,
+    pub message: String,
+    pub branch: Option<String>,
+    pub date: String,
+    pub timestamp: i64,
+}
+
+#[derive(serde::Serialize)]
+pub struct StashDetails {
+    pub entry: StashEntry,
+    pub files: Vec<ChangedFile>,
+    pub diff: Vec<FileDiff>,
+}
+
+/// Splits a stash reflog subject such as `WIP on main: 1a2b3c4 Fix typo` or
+/// `On main: my message` into the branch and the message.
+pub(crate) fn parse_stash_subject(subject: &str) -> (Option<String>, String) {
+    let rest = subject
+        .strip_prefix("WIP on ")
+        .or_else(|| subject.strip_prefix("On "));
+
+    match rest.and_then(|r| r.split_once(": ")) {
+        Some((branch, message)) => (Some(branch.to_string()), message.to_string()),
+        None => (None, subject.to_string()),
+    }
+}
+
+async fn read_stashes(repo: &Path) -> Result<Vec<StashEntry>, String> {
+    let output = Command::new("git")
+        .arg("--no-pager")
+        .arg("stash")
+        .arg("list")
+        .arg("--format=%gd%x1E%H%x1E%gs%x1E%ai%x1E%at%x1F")
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git stash list: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git stash list failed: {}", stderr));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut stashes = Vec::new();
+
+    for record in stdout.split('\u{001F}') {
+        let parts: Vec<&str> = record.trim().split('\u{001E}').collect();
+        if parts.len() < 5 {
+            continue;
+        }
+
+        let reference = parts[0].to_string();
+        let index = reference
+            .strip_prefix("stash@{")
+            .and_then(|r| r.strip_suffix('}'))
+            .and_then(|r| r.parse::<u32>().ok())
+            .unwrap_or(stashes.len() as u32);
+        let (branch, message) = parse_stash_subject(parts[2]);
+
+        stashes.push(StashEntry {
+            index,
+            reference,
+            hash: parts[1].to_string(),
+            message,
+            branch,
+            date: parts[3].to_string(),
+            timestamp: parts[4].parse().unwrap_or(0),
+        });
+    }
+
+    Ok(stashes)
+}
+
+#[tauri::command]
+pub async fn list_stashes(repo_path: String) -> Result<Vec<StashEntry>, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    read_stashes(repo).await
+}
+
+#[tauri::command]
+pub async fn push_stash(
+    repo_path: String,
+    message: Option<String>,
+    include_untracked: Option<bool>,
+    keep_index: Option<bool>,
+    paths: Option<Vec<String>>,
+) -> Result<Option<StashEntry>, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let before = Command::new("git")
+        .arg("rev-parse")
+        .arg("--verify")
+        .arg("--quiet")
+        .arg("refs/stash")
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git rev-parse: {}", e))?;
+
+    let state_before = capture_state(repo).await;
+
+    let mut cmd = Command::new("git");
+    cmd.arg("stash").arg("push");
+
+    if let Some(msg) = message.as_deref().filter(|m| !m.trim().is_empty()) {
+        cmd.arg("--message").arg(msg.trim());
+    }
+
+    if include_untracked.unwrap_or(false) {
+        cmd.arg("--include-untracked");
+    }
+
+    if keep_index.unwrap_or(false) {
+        cmd.arg("--keep-index");
+    }
+
+    if let Some(paths) = paths.filter(|p| !p.is_empty()) {
+        cmd.arg("--");
+        for path in &paths {
+            let relative_path = Path::new(path)
+                .strip_prefix(repo)
+                .ok()
+                .and_then(|p| p.to_str())
+                .unwrap_or(path);
+            cmd.arg(relative_path);
+        }
+    }
+
+    let output = cmd
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git stash push: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git stash push failed: {}", stderr));
+    }
+
+    record_operation(repo, "stash push", true, state_before).await;
+
+    let stashes = read_stashes(repo).await?;
+    let newest = stashes.into_iter().next();
+
+    // "No local changes to save" exits successfully without a new stash.
+    let previous_hash = String::from_utf8_lossy(&before.stdout).trim().to_string();
+    Ok(newest.filter(|entry| entry.hash != previous_hash))
+}
+
+async fn run_stash_command(
+    repo_path: &str,
+    subcommand: &str,
+    stash_index: u32,
+    restore_index: bool,
+) -> Result<(), String> {
+    let repo = Path::new(repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let before = capture_state(repo).await;
+
+    let mut cmd = Command::new("git");
+    cmd.arg("stash").arg(subcommand);
+
+    if restore_index {
+        cmd.arg("--index");
+    }
+
+    let output = cmd
+        .arg(format!("stash@{{{}}}", stash_index))
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git stash {}: {}", subcommand, e))?;
+
+    // A conflicted apply or pop still changes the index, so journal it either way.
+    record_operation(
+        repo,
+        &format!("stash {}", subcommand),
+        subcommand != "drop",
+        before,
+    )
+    .await;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        return Err(format!(
+            "Git stash {} failed: {}{}",
+            subcommand, stderr, stdout
+        ));
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn apply_stash(
+    repo_path: String,
+    stash_index: u32,
+    restore_index: Option<bool>,
+) -> Result<(), String> {
+    run_stash_command(
+        &repo_path,
+        "apply",
+        stash_index,
+        restore_index.unwrap_or(false),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn pop_stash(
+    repo_path: String,
+    stash_index: u32,
+    restore_index: Option<bool>,
+) -> Result<(), String> {
+    run_stash_command(
+        &repo_path,
+        "pop",
+        stash_index,
+        restore_index.unwrap_or(false),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn drop_stash(repo_path: String, stash_index: u32) -> Result<(), String> {
+    run_stash_command(&repo_path, "drop", stash_index, false).await
+}
+
+#[tauri::command]
+pub async fn get_stash_details(
+    repo_path: String,
+    stash_index: u32,
+) -> Result<StashDetails, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let entry = read_stashes(repo)
+        .await?
+        .into_iter()
+        .find(|entry| entry.index == stash_index)
+        .ok_or_else(|| format!("Stash stash@{{{}}} does not exist", stash_index))?;
+
+    let files_output = Command::new("git")
+        .arg("--no-pager")
+        .arg("stash")
+        .arg("show")
+        .arg("--include-untracked")
+        .arg("-z")
+        .arg("--raw")
+        .arg("--numstat")
+        .arg("--find-renames")
+        .arg(&entry.reference)
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git stash show: {}", e))?;
+
+    if !files_output.status.success() {
+        let stderr = String::from_utf8_lossy(&files_output.stderr);
+        return Err(format!("Git stash show failed: {}", stderr));
+    }
+
+    let diff_output = Command::new("git")
+        .arg("--no-pager")
+        .arg("stash")
+        .arg("show")
+        .arg("--include-untracked")
+        .arg("--patch")
+        .args(PATCH_FORMAT_ARGS)
+        .arg(&entry.reference)
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git stash show: {}", e))?;
+
+    if !diff_output.status.success() {
+        let stderr = String::from_utf8_lossy(&diff_output.stderr);
+        return Err(format!("Git stash show failed: {}", stderr));
+    }
+
+    let (files, _) = parse_raw_numstat(&String::from_utf8_lossy(&files_output.stdout));
+
+    Ok(StashDetails {
+        entry,
+        files,
+        diff: parse_unified_diff(&String::from_utf8_lossy(&diff_output.stdout)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_util::{commit_file, git, init_repo, path_str, write_file};
+
+    #[tokio::test]
+    async fn stash_details_report_renames_by_new_path() {
+        let repo = init_repo();
+        let lines: String = (1..=20).map(|n| format!("{}\n", n)).collect();
+        commit_file(repo.path(), "old.txt", &lines, "first");
+        commit_file(repo.path(), "m.txt", "a\n", "second");
+
+        git(repo.path(), &["mv", "old.txt", "new.txt"]);
+        write_file(repo.path(), "m.txt", "a\nb\nc\n");
+        write_file(repo.path(), "untracked.txt", "u\n");
+        push_stash(path_str(&repo), None, Some(true), None, None)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let details = get_stash_details(path_str(&repo), 0).await.unwrap();
+        let file = |path: &str| details.files.iter().find(|f| f.path == path).unwrap();
+
+        let renamed = file("new.txt");
+        assert_eq!(renamed.status, "renamed");
+        assert_eq!(renamed.old_path.as_deref(), Some("old.txt"));
+        assert_eq!(renamed.similarity, Some(100));
+
+        let modified = file("m.txt");
+        assert_eq!(modified.status, "modified");
+        assert_eq!(modified.additions, Some(2));
+        assert_eq!(modified.deletions, Some(0));
+
+        assert_eq!(file("untracked.txt").status, "added");
+        assert!(!details.files.iter().any(|f| f.path == "old.txt"));
+    }
+}
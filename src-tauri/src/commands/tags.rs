@@ -0,0 +1,452 @@
+use std::path::Path;
+use tokio::process::Command;
+
+use super::git::{parse_push_porcelain, run_git, PushResult};
+
+#[derive(serde::Serialize)]
+pub struct TagInfo {
+    pub name: String,
+    pub target: String,
+    pub object: String,
+    pub annotated: bool,
+    pub tagger_name: Option<String>,
+    pub tagger_email: Option<String>,
+    pub date: String,
+    pub timestamp: i64,
+    pub message: Option<String>,
+}
+
+#[tauri::command]
+pub async fn list_git_tags(repo_path: String) -> Result<Vec<TagInfo>, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    // creatordate is the tagger date for annotated tags and the commit date
+    // for lightweight ones.
+    let output = Command::new("git")
+        .arg("for-each-ref")
+        .arg("--sort=-creatordate")
+        .arg(
+            "--format=%(refname:strip=2)%1E%(objecttype)%1E%(objectname)%1E%(*objectname)\
+             %1E%(taggername)%1E%(taggeremail:trim)%1E%(creatordate:iso)%1E%(creatordate:unix)\
+             %1E%(contents:subject)%1E%(contents:body)%1F",
+        )
+        .arg("refs/tags")
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git for-each-ref: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git for-each-ref failed: {}", stderr));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut tags = Vec::new();
+
+    for record in stdout.split('\u{001F}') {
+        let parts: Vec<&str> = record.trim_start_matches('\n').split('\u{001E}').collect();
+        if parts.len() < 10 {
+            continue;
+        }
+
+        let annotated = parts[1] == "tag";
+        let object = parts[2].to_string();
+        let non_empty = |value: &str| Some(value.trim().to_string()).filter(|v| !v.is_empty());
+
+        let message = if annotated {
+            let subject = parts[8].trim();
+            let body = parts[9].trim();
+            Some(if body.is_empty() {
+                subject.to_string()
+            } else {
+                format!("{}\n\n{}", subject, body)
+            })
+        } else {
+            None
+        };
+
+        tags.push(TagInfo {
+            name: parts[0].to_string(),
+            target: if annotated {
+                parts[3].to_string()
+            } else {
+                object.clone()
+            },
+            object,
+            annotated,
+            tagger_name: non_empty(parts[4]),
+            tagger_email: non_empty(parts[5]),
+            date: parts[6].to_string(),
+            timestamp: parts[7].parse().unwrap_or(0),
+            message,
+        });
+    }
+
+    Ok(tags)
+}
+
+/// Trims `tag_name` and checks it the way `git tag` does: `refs/tags/<name>`
+/// must be a valid ref and the name must not start with `-`.
+async fn checked_tag_name(repo: &Path, tag_name: &str) -> Result<String, String> {
+    let tag_name = tag_name.trim();
+    if tag_name.is_empty() {
+        return Err("Tag name cannot be empty".to_string());
+    }
+
+    let invalid = || format!("'{}' is not a valid tag name", tag_name);
+    if tag_name.starts_with('-') {
+        return Err(invalid());
+    }
+    run_git(
+        repo,
+        &["check-ref-format", &format!("refs/tags/{}", tag_name)],
+    )
+    .await
+    .map_err(|_| invalid())?;
+
+    Ok(tag_name.to_string())
+}
+
+/// Creates an annotated tag when a message is given, otherwise a
+/// lightweight one. `target` defaults to HEAD.
+#[tauri::command]
+pub async fn create_git_tag(
+    repo_path: String,
+    tag_name: String,
+    target: Option<String>,
+    message: Option<String>,
+    force: Option<bool>,
+) -> Result<(), String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let tag_name = checked_tag_name(repo, &tag_name).await?;
+
+    let mut cmd = Command::new("git");
+    cmd.arg("tag");
+
+    if let Some(msg) = message.as_deref().filter(|m| !m.trim().is_empty()) {
+        cmd.arg("--annotate").arg("--message").arg(msg);
+    }
+
+    if force.unwrap_or(false) {
+        cmd.arg("--force");
+    }
+
+    cmd.arg("--end-of-options").arg(&tag_name);
+
+    if let Some(target) = target.as_deref().filter(|t| !t.trim().is_empty()) {
+        cmd.arg(target.trim());
+    }
+
+    let output = cmd
+        .env("LC_ALL", "C")
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git tag: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("already exists") {
+            return Err(format!("Tag '{}' already exists", tag_name));
+        }
+        return Err(format!("Git tag failed: {}", stderr));
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_git_tag(
+    repo_path: String,
+    tag_name: String,
+    delete_local: Option<bool>,
+    remote: Option<String>,
+) -> Result<(), String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let tag_name = checked_tag_name(repo, &tag_name).await?;
+
+    if delete_local.unwrap_or(true) {
+        let output = Command::new("git")
+            .arg("tag")
+            .arg("--delete")
+            .arg("--end-of-options")
+            .arg(&tag_name)
+            .current_dir(repo)
+            .output()
+            .await
+            .map_err(|e| format!("Failed to execute git tag --delete: {}", e))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Git tag delete failed: {}", stderr));
+        }
+    }
+
+    if let Some(remote) = remote.as_deref().map(str::trim).filter(|r| !r.is_empty()) {
+        let output = Command::new("git")
+            .arg("push")
+            .arg("--delete")
+            .arg("--end-of-options")
+            .arg(remote)
+            .arg(format!("refs/tags/{}", tag_name))
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .current_dir(repo)
+            .output()
+            .await
+            .map_err(|e| format!("Failed to execute git push --delete: {}", e))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!(
+                "Deleting tag '{}' from '{}' failed: {}",
+                tag_name, remote, stderr
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Pushes a single tag, or every local tag when `tag_name` is omitted.
+#[tauri::command]
+pub async fn push_git_tags(
+    repo_path: String,
+    remote: Option<String>,
+    tag_name: Option<String>,
+    force: Option<bool>,
+) -> Result<PushResult, String> {
+    let repo = Path::new(&repo_path);
+
+    if !repo.exists() {
+        return Err("Repository path does not exist".to_string());
+    }
+
+    let git_dir = repo.join(".git");
+    if !git_dir.exists() {
+        return Err("Not a git repository".to_string());
+    }
+
+    let remote = remote
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .unwrap_or_else(|| "origin".to_string());
+
+    let mut cmd = Command::new("git");
+    cmd.arg("push").arg("--porcelain");
+
+    if force.unwrap_or(false) {
+        cmd.arg("--force");
+    }
+
+    let refspec = match tag_name.filter(|t| !t.trim().is_empty()) {
+        Some(tag) => {
+            let tag = checked_tag_name(repo, &tag).await?;
+            Some(format!("refs/tags/{0}:refs/tags/{0}", tag))
+        }
+        None => {
+            cmd.arg("--tags");
+            None
+        }
+    };
+
+    cmd.arg("--end-of-options").arg(&remote).args(refspec);
+
+    let output = cmd
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("LC_ALL", "C")
+        .current_dir(repo)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute git push: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let updates = parse_push_porcelain(&stdout);
+
+    let rejected = updates.iter().any(|u| u.status == "rejected");
+
+    if !output.status.success() && !rejected {
+        return Err(format!("Git push failed: {}", stderr));
+    }
+
+    Ok(PushResult {
+        remote,
+        success: output.status.success(),
+        rejected,
+        non_fast_forward: false,
+        upstream_set: false,
+        updates,
+        message: stderr.trim().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_util::{commit_file, git, init_repo, path_str};
+    use tempfile::TempDir;
+
+    /// A repository with two commits and a bare remote named `origin`.
+    fn repo_with_remote() -> (TempDir, TempDir, String) {
+        let repo = init_repo();
+        let remote = TempDir::new().expect("failed to create temp dir");
+        git(remote.path(), &["init", "-q", "--bare"]);
+        let first = commit_file(repo.path(), "a.txt", "1\n", "first");
+        commit_file(repo.path(), "a.txt", "2\n", "second");
+        git(
+            repo.path(),
+            &["remote", "add", "origin", &remote.path().to_string_lossy()],
+        );
+        (repo, remote, first)
+    }
+
+    #[tokio::test]
+    async fn creates_lightweight_and_annotated_tags() {
+        let (repo, _remote, first) = repo_with_remote();
+
+        create_git_tag(
+            path_str(&repo),
+            "v1".to_string(),
+            Some(first.clone()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        create_git_tag(
+            path_str(&repo),
+            "v2".to_string(),
+            None,
+            Some("Release 2\n\nNotes".to_string()),
+            None,
+        )
+        .await
+        .unwrap();
+
+        let tags = list_git_tags(path_str(&repo)).await.unwrap();
+        let v1 = tags.iter().find(|t| t.name == "v1").unwrap();
+        assert!(!v1.annotated);
+        assert_eq!(v1.target, first);
+        assert_eq!(v1.message, None);
+
+        let v2 = tags.iter().find(|t| t.name == "v2").unwrap();
+        assert!(v2.annotated);
+        assert_eq!(v2.target, git(repo.path(), &["rev-parse", "HEAD"]));
+        assert_ne!(v2.object, v2.target);
+        assert_eq!(v2.tagger_email.as_deref(), Some("test@example.com"));
+        assert_eq!(v2.message.as_deref(), Some("Release 2\n\nNotes"));
+
+        let again = create_git_tag(path_str(&repo), "v1".to_string(), None, None, None).await;
+        assert!(matches!(again, Err(e) if e == "Tag 'v1' already exists"));
+        create_git_tag(path_str(&repo), "v1".to_string(), None, None, Some(true))
+            .await
+            .unwrap();
+        assert_eq!(
+            git(repo.path(), &["rev-parse", "v1"]),
+            git(repo.path(), &["rev-parse", "HEAD"])
+        );
+    }
+
+    #[tokio::test]
+    async fn deletes_tags_locally_and_on_the_remote() {
+        let (repo, remote, _) = repo_with_remote();
+        git(repo.path(), &["tag", "v1"]);
+        git(repo.path(), &["tag", "v2"]);
+
+        let pushed = push_git_tags(path_str(&repo), Some("origin".to_string()), None, None)
+            .await
+            .unwrap();
+        assert!(pushed.success);
+        assert_eq!(git(remote.path(), &["tag"]), "v1\nv2");
+
+        delete_git_tag(
+            path_str(&repo),
+            "v1".to_string(),
+            None,
+            Some("origin".to_string()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(git(repo.path(), &["tag"]), "v2");
+        assert_eq!(git(remote.path(), &["tag"]), "v2");
+    }
+
+    #[tokio::test]
+    async fn rejects_tag_names_that_are_not_refs() {
+        let (repo, remote, _) = repo_with_remote();
+
+        for name in ["-f", "--delete", "a..b", "bad name", "v1.lock"] {
+            let created = create_git_tag(path_str(&repo), name.to_string(), None, None, None).await;
+            assert!(
+                matches!(&created, Err(e) if *e == format!("'{}' is not a valid tag name", name)),
+                "{}",
+                name
+            );
+        }
+        assert_eq!(git(repo.path(), &["tag"]), "");
+
+        git(repo.path(), &["tag", "v1"]);
+        let deleted = delete_git_tag(path_str(&repo), "--list".to_string(), None, None).await;
+        assert!(matches!(deleted, Err(e) if e.contains("not a valid tag name")));
+
+        let pushed = push_git_tags(
+            path_str(&repo),
+            Some("origin".to_string()),
+            Some("-v1".to_string()),
+            None,
+        )
+        .await;
+        assert!(matches!(pushed, Err(e) if e.contains("not a valid tag name")));
+        assert_eq!(git(remote.path(), &["tag"]), "");
+    }
+
+    #[tokio::test]
+    async fn targets_and_remotes_are_never_parsed_as_options() {
+        let (repo, _remote, _) = repo_with_remote();
+
+        let created = create_git_tag(
+            path_str(&repo),
+            "v1".to_string(),
+            Some("--list".to_string()),
+            None,
+            None,
+        )
+        .await;
+        assert!(matches!(created, Err(e) if e.contains("Git tag failed")));
+        assert_eq!(git(repo.path(), &["tag"]), "");
+
+        let pushed = push_git_tags(path_str(&repo), Some("--all".to_string()), None, None).await;
+        assert!(matches!(pushed, Err(e) if e.contains("Git push failed")));
+    }
+}
@@ -0,0 +1,55 @@
+//! Helpers for tests that run the commands against throwaway repositories.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+/// Runs git in `dir` and returns its trimmed stdout, panicking on failure.
+pub(crate) fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .env("LC_ALL", "C")
+        .output()
+        .expect("failed to run git");
+
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// Creates an empty repository on `main` with a local identity.
+pub(crate) fn init_repo() -> TempDir {
+    let dir = TempDir::new().expect("failed to create temp dir");
+    git(dir.path(), &["init", "-q", "-b", "main"]);
+    git(dir.path(), &["config", "user.name", "Test"]);
+    git(dir.path(), &["config", "user.email", "test@example.com"]);
+    git(dir.path(), &["config", "commit.gpgsign", "false"]);
+    dir
+}
+
+/// Writes `content` to `name` and commits it, returning the new HEAD.
+pub(crate) fn commit_file(dir: &Path, name: &str, content: &str, message: &str) -> String {
+    write_file(dir, name, content);
+    git(dir, &["add", "--", name]);
+    git(dir, &["commit", "-q", "-m", message]);
+    git(dir, &["rev-parse", "HEAD"])
+}
+
+pub(crate) fn write_file(dir: &Path, name: &str, content: &str) {
+    let path = dir.join(name);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).expect("failed to create parent dir");
+    }
+    fs::write(path, content).expect("failed to write file");
+}
+
+pub(crate) fn path_str(dir: &TempDir) -> String {
+    dir.path().to_string_lossy().to_string()
+}
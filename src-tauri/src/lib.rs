@@ -1,16 +1,51 @@
 mod commands;
 
+use commands::cherry_pick::{
+    cherry_pick_abort, cherry_pick_commits, cherry_pick_continue, revert_abort, revert_commits,
+    revert_continue,
+};
+use commands::diff::{get_git_commit_diff, get_git_revision_diff};
+use commands::discard::{
+    discard_file_changes, discard_hunks, discard_untracked, list_discarded_changes, preview_clean,
+    restore_discarded_changes,
+};
 use commands::fs::{
     get_disk_space, get_folder_size, get_subfolders_total_size, list_directory, read_file,
 };
 use commands::git::{
-    checkout_git_branch, commit_changes, get_commit_details, get_current_git_branch,
-    get_git_blame, get_git_branches, get_git_commits, get_git_diff, get_git_remote_origin,
-    get_git_status, get_git_version, is_git_repo, list_git_repos, pull_git_repo, stage_file,
-    unstage_file,
+    checkout_git_branch, clone_git_repo, commit_changes, create_git_branch, delete_git_branch,
+    fetch_git_remote, get_commit_details, get_current_git_branch, get_git_blame, get_git_branches,
+    get_git_commits, get_git_diff, get_git_remote_origin, get_git_status, get_git_version,
+    is_git_repo, list_git_repos, pull_git_repo, push_git_branch, query_git_commits,
+    rename_git_branch, set_branch_upstream, stage_file, unstage_file,
 };
 use commands::global_search::global_search;
+use commands::graph::get_commit_graph;
+use commands::journal::{list_operations, redo_last_operation, undo_last_operation};
+use commands::merge::{
+    abort_merge, get_conflict_details, list_conflicts, mark_conflict_resolved, merge_git_branch,
+    resolve_conflict_take,
+};
+use commands::patch::{stage_hunks, unstage_hunks};
+use commands::rebase::{
+    interactive_rebase, rebase_abort, rebase_continue, rebase_onto, rebase_skip,
+};
+use commands::reflog::{get_reflog, restore_reflog_entry};
+use commands::reset::{
+    delete_reset_snapshot, list_reset_snapshots, reset_to_commit, restore_reset_snapshot,
+};
+use commands::stash::{
+    apply_stash, drop_stash, get_stash_details, list_stashes, pop_stash, push_stash,
+};
 use commands::system::get_system_info;
+use commands::tags::{create_git_tag, delete_git_tag, list_git_tags, push_git_tags};
+
+/// Lets the binary double as git's editor during an interactive rebase.
+/// Returns the exit code when it was launched that way.
+pub fn run_git_editor() -> Option<i32> {
+    let args: Vec<String> = std::env::args().collect();
+    commands::rebase::run_as_editor(&args)
+}
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -26,18 +61,73 @@ pub fn run() {
             get_git_version,
             list_git_repos,
             is_git_repo,
+            clone_git_repo,
             get_git_status,
             get_git_blame,
             get_git_branches,
             get_current_git_branch,
             checkout_git_branch,
+            create_git_branch,
+            rename_git_branch,
+            delete_git_branch,
+            set_branch_upstream,
             pull_git_repo,
+            push_git_branch,
+            fetch_git_remote,
+            list_git_tags,
+            create_git_tag,
+            delete_git_tag,
+            push_git_tags,
             stage_file,
             unstage_file,
+            stage_hunks,
+            unstage_hunks,
+            discard_file_changes,
+            discard_hunks,
+            preview_clean,
+            discard_untracked,
+            list_discarded_changes,
+            restore_discarded_changes,
+            list_stashes,
+            push_stash,
+            apply_stash,
+            pop_stash,
+            drop_stash,
+            get_stash_details,
+            merge_git_branch,
+            abort_merge,
+            list_conflicts,
+            get_conflict_details,
+            resolve_conflict_take,
+            mark_conflict_resolved,
+            cherry_pick_commits,
+            cherry_pick_continue,
+            cherry_pick_abort,
+            revert_commits,
+            revert_continue,
+            revert_abort,
+            rebase_onto,
+            rebase_continue,
+            rebase_skip,
+            rebase_abort,
+            interactive_rebase,
+            reset_to_commit,
+            list_reset_snapshots,
+            restore_reset_snapshot,
+            delete_reset_snapshot,
+            get_reflog,
+            restore_reflog_entry,
+            list_operations,
+            undo_last_operation,
+            redo_last_operation,
             commit_changes,
             get_git_commits,
+            query_git_commits,
+            get_commit_graph,
             get_commit_details,
             get_git_diff,
+            get_git_revision_diff,
+            get_git_commit_diff,
             get_git_remote_origin,
             list_directory,
             read_file,
@@ -2,5 +2,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    if let Some(code) = git_client_lib::run_git_editor() {
+        std::process::exit(code);
+    }
+
     git_client_lib::run()
 }
@@ -14,6 +14,10 @@ import {
 } from "@/components/ui/sidebar";
 import { Textarea } from "@/components/ui/textarea";
 import {
+  getStatusLabel,
+  isStagedEntry,
+  isUnstagedEntry,
+  type RepoStatus,
   useCommitChanges,
   useGitStatus,
   useStageFile,
@@ -26,32 +30,22 @@ type FileChange = {
   isStaged: boolean;
 };
 
-function parseGitStatus(gitStatus: Record<string, string>): {
+function parseGitStatus(gitStatus: RepoStatus | undefined): {
   staged: FileChange[];
   unstaged: FileChange[];
 } {
   const staged: FileChange[] = [];
   const unstaged: FileChange[] = [];
 
-  for (const [filePath, status] of Object.entries(gitStatus)) {
-    const isStaged =
-      status === "added" ||
-      status === "modified-staged" ||
-      status === "added-modified" ||
-      status === "added-deleted" ||
-      status === "deleted" ||
-      status === "renamed" ||
-      status === "copied";
-    const change: FileChange = {
-      path: filePath,
-      status,
-      isStaged,
-    };
-
-    if (isStaged) {
-      staged.push(change);
-    } else {
-      unstaged.push(change);
+  for (const entry of gitStatus?.entries ?? []) {
+    const status = getStatusLabel(entry);
+
+    if (isStagedEntry(entry)) {
+      staged.push({ path: entry.path, status, isStaged: true });
+    }
+
+    if (isUnstagedEntry(entry)) {
+      unstaged.push({ path: entry.path, status, isStaged: false });
     }
   }
 
@@ -63,7 +57,7 @@ function parseGitStatus(gitStatus: Record<string, string>): {
 
 export function ChangesContent({ repoPath }: { repoPath: string }) {
   const navigate = useNavigate();
-  const { data: gitStatus = {}, isLoading } = useGitStatus(repoPath);
+  const { data: gitStatus, isLoading } = useGitStatus(repoPath);
   const stageMutation = useStageFile();
   const unstageMutation = useUnstageFile();
   const commitMutation = useCommitChanges();
@@ -4,26 +4,36 @@ import { DiffLine } from "./diff-viewer/components/diff-line";
 import { DiffStats } from "./diff-viewer/components/diff-stats";
 import { useDiffHighlighting } from "./diff-viewer/hooks/use-diff-highlighting";
 import type { DiffViewerProps } from "./diff-viewer/types";
-import { getFileName, parseDiff } from "./diff-viewer/utils";
+import { contentAsLines, flattenDiff, getFileName } from "./diff-viewer/utils";
 
-export function DiffViewer({ filePath, diffContent }: DiffViewerProps) {
-  const parsedLines = useMemo(() => parseDiff(diffContent), [diffContent]);
+export function DiffViewer({ filePath, files, content }: DiffViewerProps) {
+  const parsedLines = useMemo(
+    () =>
+      files.length === 0 && content !== undefined
+        ? contentAsLines(content)
+        : flattenDiff(files),
+    [files, content]
+  );
   const fileName = getFileName(filePath);
   const { highlightedLines } = useDiffHighlighting(parsedLines, fileName);
 
-  const diffStats = useMemo(() => {
-    const additions = parsedLines.filter(
-      (line) => line.type === "added"
-    ).length;
-    const deletions = parsedLines.filter(
-      (line) => line.type === "removed"
-    ).length;
-    return { additions, deletions };
-  }, [parsedLines]);
+  const diffStats = useMemo(
+    () => ({
+      additions: files.reduce((sum, file) => sum + file.additions, 0),
+      deletions: files.reduce((sum, file) => sum + file.deletions, 0),
+    }),
+    [files]
+  );
+  const isBinary = files.some((file) => file.binary);
 
   return (
     <div className="flex flex-1 flex-col overflow-auto">
       <div className="flex flex-1 flex-col font-mono text-sm">
+        {isBinary && (
+          <div className="px-4 py-2 text-muted-foreground text-xs">
+            Binary file changed
+          </div>
+        )}
         {highlightedLines.map((line, index) => {
           const baseClassName = "py-0.5";
           let lineClassName = "";
@@ -52,6 +62,7 @@ export function DiffViewer({ filePath, diffContent }: DiffViewerProps) {
               lineIndex={index}
               lineType={line.type}
               newLineNumber={line.newLineNumber}
+              noNewline={line.noNewline}
               oldLineNumber={line.oldLineNumber}
               prefix=""
               tokens={line.tokens}
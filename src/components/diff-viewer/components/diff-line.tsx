@@ -11,6 +11,7 @@ type DiffLineProps = {
   lineIndex: number;
   oldLineNumber?: number;
   newLineNumber?: number;
+  noNewline?: boolean;
   lineType: "removed" | "added" | "context";
 };
 
@@ -23,6 +24,7 @@ export function DiffLine({
   lineIndex,
   oldLineNumber,
   newLineNumber,
+  noNewline,
   lineType,
 }: DiffLineProps) {
   let oldLineTextColor = "text-muted-foreground/60";
@@ -66,6 +68,11 @@ export function DiffLine({
               />
             ))
           : content || " "}
+        {noNewline && (
+          <span className="ml-2 select-none text-muted-foreground/50 text-xs">
+            No newline at end of file
+          </span>
+        )}
       </span>
     </div>
   );
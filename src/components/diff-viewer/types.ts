@@ -1,8 +1,11 @@
 import type { ThemedToken } from "shiki";
+import type { FileDiff } from "@/hooks/tauri-queries";
 
 export type DiffViewerProps = {
   filePath: string;
-  diffContent: string;
+  files: FileDiff[];
+  /** Plain file content shown when there is no diff to display. */
+  content?: string;
 };
 
 export type DiffLine = {
@@ -11,4 +14,5 @@ export type DiffLine = {
   tokens?: ThemedToken[];
   oldLineNumber?: number;
   newLineNumber?: number;
+  noNewline?: boolean;
 };
@@ -4,7 +4,7 @@ import { useEffect, useState } from "react";
 import { SidebarMenu } from "@/components/ui/sidebar";
 import {
   useCurrentGitBranch,
-  useGitStatus,
+  useGitStatusMap,
   useListDirectory,
   usePullGitRepo,
 } from "@/hooks/tauri-queries";
@@ -124,7 +124,7 @@ export function FileTree({ rootPath, searchQuery = "" }: FileTreeProps) {
   const { data: entries, isLoading: isLoadingEntries } =
     useListDirectory(rootPath);
   const { data: gitStatus = {}, isLoading: isLoadingStatus } =
-    useGitStatus(rootPath);
+    useGitStatusMap(rootPath);
   const { data: currentBranch } = useCurrentGitBranch(rootPath);
   const pullMutation = usePullGitRepo();
 
@@ -31,14 +31,23 @@ export function BranchSelector() {
     useCurrentGitBranch(currentRepo);
   const checkoutMutation = useCheckoutGitBranch();
 
-  const branches = useMemo<Branch[]>(
-    () =>
-      branchList.map((branch) => ({
-        label: branch,
-        value: branch,
-      })),
-    [branchList]
-  );
+  const branches = useMemo<Branch[]>(() => {
+    // Remote-only branches are listed by their plain name so checking them
+    // out creates a local tracking branch. Refs outside any remote can't be
+    // tracked, so they are left out.
+    const names = new Set<string>();
+    for (const branch of branchList) {
+      if (branch.kind !== "remote") {
+        names.add(branch.short_name);
+      } else if (branch.remote) {
+        names.add(branch.short_name.slice(branch.remote.length + 1));
+      }
+    }
+    return [...names].sort().map((name) => ({
+      label: name,
+      value: name,
+    }));
+  }, [branchList]);
 
   const currentBranch = useMemo<Branch | null>(
     () => branches.find((b) => b.value === currentBranchName) || null,
@@ -6,15 +6,102 @@ import {
 } from "@tanstack/react-query";
 import { invoke } from "@tauri-apps/api/core";
 
+export type StatusEntry = {
+  path: string;
+  original_path: string | null;
+  kind: "changed" | "renamed" | "copied" | "unmerged" | "untracked" | "ignored";
+  index_status: string;
+  worktree_status: string;
+  conflict: string | null;
+  similarity: number | null;
+  submodule: {
+    commit_changed: boolean;
+    tracked_changes: boolean;
+    untracked_changes: boolean;
+  } | null;
+};
+
+export type RepoStatus = {
+  branch: {
+    oid: string | null;
+    head: string | null;
+    upstream: string | null;
+    ahead: number;
+    behind: number;
+  };
+  entries: StatusEntry[];
+};
+
+export function isStagedEntry(entry: StatusEntry): boolean {
+  return (
+    entry.kind !== "unmerged" &&
+    entry.index_status !== "unmodified" &&
+    entry.index_status !== "untracked" &&
+    entry.index_status !== "ignored"
+  );
+}
+
+export function isUnstagedEntry(entry: StatusEntry): boolean {
+  return (
+    entry.kind === "unmerged" ||
+    (entry.worktree_status !== "unmodified" &&
+      entry.worktree_status !== "ignored")
+  );
+}
+
+export function getStatusLabel(entry: StatusEntry): string {
+  if (entry.kind !== "changed") {
+    return entry.kind;
+  }
+  if (entry.index_status === "added") {
+    if (entry.worktree_status === "modified") {
+      return "added-modified";
+    }
+    if (entry.worktree_status === "deleted") {
+      return "added-deleted";
+    }
+    return "added";
+  }
+  if (entry.index_status === "modified") {
+    return "modified-staged";
+  }
+  if (
+    entry.index_status === "deleted" ||
+    entry.worktree_status === "deleted"
+  ) {
+    return "deleted";
+  }
+  if (
+    entry.worktree_status === "modified" ||
+    entry.worktree_status === "type-changed"
+  ) {
+    return "modified";
+  }
+  return "unknown";
+}
+
 export function useGitStatus(repoPath: string | null) {
   return useQuery({
     queryKey: ["git-status", repoPath],
-    queryFn: () =>
-      invoke<Record<string, string>>("get_git_status", { repoPath }),
+    queryFn: () => invoke<RepoStatus>("get_git_status", { repoPath }),
     enabled: !!repoPath,
   });
 }
 
+export function useGitStatusMap(repoPath: string | null) {
+  return useQuery({
+    queryKey: ["git-status", repoPath],
+    queryFn: () => invoke<RepoStatus>("get_git_status", { repoPath }),
+    enabled: !!repoPath,
+    select: (status): Record<string, string> =>
+      Object.fromEntries(
+        status.entries
+          .filter((entry) => entry.kind !== "ignored")
+          .map((entry) => [entry.path, getStatusLabel(entry)])
+      ),
+  });
+}
+
 export function useListDirectory(path: string | null) {
   return useQuery({
     queryKey: ["list-directory", path],
@@ -35,10 +122,25 @@ export function useReadFile(path: string | null) {
   });
 }
 
+export type BranchInfo = {
+  ref_name: string;
+  short_name: string;
+  kind: "local" | "remote";
+  remote: string | null;
+  upstream: string | null;
+  upstream_gone: boolean;
+  ahead: number;
+  behind: number;
+  hash: string;
+  subject: string;
+  author_date: string;
+  is_current: boolean;
+};
+
 export function useGitBranches(repoPath: string | null) {
   return useQuery({
     queryKey: ["git-branches", repoPath],
-    queryFn: () => invoke<string[]>("get_git_branches", { repoPath }),
+    queryFn: () => invoke<BranchInfo[]>("get_git_branches", { repoPath }),
     enabled: !!repoPath,
   });
 }
@@ -206,29 +308,149 @@ export function useGitCommits(repoPath: string | null) {
   });
 }
 
+export type CommitFilter = {
+  refs?: string[];
+  all?: boolean;
+  author?: string;
+  committer?: string;
+  message?: string;
+  ignore_case?: boolean;
+  since?: string;
+  until?: string;
+  paths?: string[];
+  follow?: boolean;
+  merges_only?: boolean;
+  no_merges?: boolean;
+  first_parent?: boolean;
+};
+
+export function useFilteredGitCommits(
+  repoPath: string | null,
+  filter: CommitFilter
+) {
+  return useInfiniteQuery({
+    queryKey: ["git-commits", repoPath, filter],
+    queryFn: ({ pageParam = 0 }) =>
+      invoke<Commit[]>("query_git_commits", {
+        repoPath,
+        filter,
+        skip: pageParam as number,
+        limit: COMMITS_PAGE_SIZE,
+      }),
+    enabled: !!repoPath,
+    initialPageParam: 0,
+    getNextPageParam: (lastPage, allPages) => {
+      if (!lastPage || lastPage.length < COMMITS_PAGE_SIZE) {
+        return;
+      }
+      return allPages.length * COMMITS_PAGE_SIZE;
+    },
+  });
+}
+
+type CommitRef = {
+  name: string;
+  full_name: string;
+  kind: "head" | "local" | "remote" | "tag" | "other";
+  is_head: boolean;
+};
+
+export type Trailer = {
+  key: string;
+  value: string;
+};
+
+export type Identity = {
+  name: string;
+  email: string;
+};
+
 type Commit = {
   hash: string;
+  parents: string[];
+  refs: CommitRef[];
   author: string;
   email: string;
   date: string;
   message: string;
+  body: string;
+  trailers: Trailer[];
+  co_authors: Identity[];
+};
+
+export type GraphEdge = {
+  from_lane: number;
+  to_lane: number;
+  color: number;
 };
 
+export type GraphRow = {
+  commit: Commit;
+  lane: number;
+  color: number;
+  edges: GraphEdge[];
+  width: number;
+};
+
+export function useCommitGraph(repoPath: string | null) {
+  return useInfiniteQuery({
+    queryKey: ["commit-graph", repoPath],
+    queryFn: ({ pageParam = 0 }) =>
+      invoke<GraphRow[]>("get_commit_graph", {
+        repoPath,
+        skip: pageParam as number,
+        limit: COMMITS_PAGE_SIZE,
+      }),
+    enabled: !!repoPath,
+    initialPageParam: 0,
+    getNextPageParam: (lastPage, allPages) => {
+      if (!lastPage || lastPage.length < COMMITS_PAGE_SIZE) {
+        return;
+      }
+      return allPages.length * COMMITS_PAGE_SIZE;
+    },
+  });
+}
+
 type CommitDetails = {
   hash: string;
+  tree: string;
+  parents: string[];
+  refs: CommitRef[];
   author: string;
   email: string;
   date: string;
+  author_timestamp: number;
+  author_tz_offset: number;
+  committer: string;
+  committer_email: string;
+  committer_date: string;
+  committer_timestamp: number;
+  committer_tz_offset: number;
   message: string;
+  body: string;
+  trailers: Trailer[];
+  co_authors: Identity[];
   files: ChangedFile[];
-  stats: string;
+  stats: DiffTotals;
 };
 
 type ChangedFile = {
   status: string;
   path: string;
-  additions?: number;
-  deletions?: number;
+  old_path: string | null;
+  additions: number | null;
+  deletions: number | null;
+  binary: boolean;
+  old_mode: string | null;
+  new_mode: string | null;
+  similarity: number | null;
+};
+
+type DiffTotals = {
+  files: number;
+  insertions: number;
+  deletions: number;
 };
 
 export function useCommitDetails(
@@ -246,11 +468,44 @@ export function useCommitDetails(
   });
 }
 
+export type DiffLineKind = "context" | "added" | "removed";
+
+export type DiffLine = {
+  kind: DiffLineKind;
+  content: string;
+  old_line: number | null;
+  new_line: number | null;
+  no_newline: boolean;
+};
+
+export type DiffHunk = {
+  header: string;
+  old_start: number;
+  old_lines: number;
+  new_start: number;
+  new_lines: number;
+  section: string;
+  lines: DiffLine[];
+};
+
+export type FileDiff = {
+  status: string;
+  old_path: string | null;
+  new_path: string | null;
+  old_mode: string | null;
+  new_mode: string | null;
+  similarity: number | null;
+  binary: boolean;
+  additions: number;
+  deletions: number;
+  hunks: DiffHunk[];
+};
+
 export function useGitDiff(repoPath: string | null, filePath: string | null) {
   return useQuery({
     queryKey: ["git-diff", repoPath, filePath],
     queryFn: () =>
-      invoke<string>("get_git_diff", {
+      invoke<FileDiff[]>("get_git_diff", {
         repoPath,
         filePath,
       }),
@@ -258,6 +513,51 @@ export function useGitDiff(repoPath: string | null, filePath: string | null) {
   });
 }
 
+export type DiffSide =
+  | { kind: "worktree" }
+  | { kind: "index" }
+  | { kind: "commit"; rev: string };
+
+export function useRevisionDiff(
+  repoPath: string | null,
+  source: DiffSide,
+  target: DiffSide,
+  filePath?: string | null,
+  oldFilePath?: string | null
+) {
+  return useQuery({
+    queryKey: ["git-revision-diff", repoPath, source, target, filePath, oldFilePath],
+    queryFn: () =>
+      invoke<FileDiff[]>("get_git_revision_diff", {
+        repoPath,
+        source,
+        target,
+        filePath,
+        oldFilePath,
+      }),
+    enabled: !!repoPath,
+  });
+}
+
+export function useCommitDiff(
+  repoPath: string | null,
+  commitHash: string | null,
+  filePath?: string | null,
+  oldFilePath?: string | null
+) {
+  return useQuery({
+    queryKey: ["git-commit-diff", repoPath, commitHash, filePath, oldFilePath],
+    queryFn: () =>
+      invoke<FileDiff[]>("get_git_commit_diff", {
+        repoPath,
+        commitHash,
+        filePath,
+        oldFilePath,
+      }),
+    enabled: !!(repoPath && commitHash),
+  });
+}
+
 export function useGitBlame(
   repoPath: string | null,
   filePath: string | null,
@@ -5,7 +5,7 @@ import { Badge } from "@/components/ui/badge";
 import { Card } from "@/components/ui/card";
 import { ScrollArea } from "@/components/ui/scroll-area";
 import { Skeleton } from "@/components/ui/skeleton";
-import { useGitStatus } from "@/hooks/tauri-queries";
+import { useGitStatusMap } from "@/hooks/tauri-queries";
 import { useRepoStore } from "@/stores/repo";
 
 type GitStatus =
@@ -89,7 +89,7 @@ type GroupedChanges = {
 export default function ProjectChanges() {
   const navigate = useNavigate();
   const currentRepo = useRepoStore((state) => state.currentRepo);
-  const { data: gitStatus = {}, isLoading } = useGitStatus(currentRepo);
+  const { data: gitStatus = {}, isLoading } = useGitStatusMap(currentRepo);
 
   const groupedChanges = useMemo<GroupedChanges>(() => {
     const groups: GroupedChanges = {};
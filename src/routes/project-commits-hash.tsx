@@ -154,10 +154,17 @@ export default function ProjectCommitDetails() {
                           {getStatusBadge(file.status)}
                         </Badge>
                         <span className="truncate font-mono text-foreground text-xs">
-                          {file.path}
+                          {file.old_path
+                            ? `${file.old_path} → ${file.path}`
+                            : file.path}
                         </span>
-                        {file.additions !== undefined &&
-                          file.deletions !== undefined && (
+                        {file.binary && (
+                          <span className="ml-auto text-muted-foreground text-xs">
+                            Binary
+                          </span>
+                        )}
+                        {file.additions !== null &&
+                          file.deletions !== null && (
                             <div className="ml-auto flex items-center gap-2 text-muted-foreground text-xs">
                               {file.additions > 0 && (
                                 <span className="text-green-600 dark:text-green-400">
@@ -218,12 +225,15 @@ export default function ProjectCommitDetails() {
               </div>
             </Card>
 
-            {details.stats && (
+            {details.stats.files > 0 && (
               <Card className="p-4">
                 <div className="space-y-2">
                   <h3 className="font-medium text-sm">Statistics</h3>
                   <div className="rounded-md bg-muted/30 p-3 font-mono text-xs">
-                    {details.stats}
+                    {details.stats.files}{" "}
+                    {details.stats.files === 1 ? "file" : "files"} changed,{" "}
+                    {details.stats.insertions} insertions(+),{" "}
+                    {details.stats.deletions} deletions(-)
                   </div>
                 </div>
               </Card>
@@ -7,10 +7,16 @@ import {
   ContextMenuPopup,
   ContextMenuTrigger,
 } from "@/components/ui/context-menu";
-import { useGitDiff, useReadFile } from "@/hooks/tauri-queries";
+import {
+  type FileDiff,
+  useGitDiff,
+  useReadFile,
+} from "@/hooks/tauri-queries";
 import { useRepoStore } from "@/stores/repo";
 import { useTabsStore } from "@/stores/tabs";
 
+const NO_FILES: FileDiff[] = [];
+
 export default function ProjectFilesFilepathDiff() {
   const params = useParams();
   const splat = params["*"];
@@ -22,7 +28,7 @@ export default function ProjectFilesFilepathDiff() {
   const activeTabId = useTabsStore((state) => state.activeTabId);
 
   const {
-    data: diffContent,
+    data: diffFiles,
     isLoading: isLoadingDiff,
     error: diffError,
   } = useGitDiff(currentRepo, decodedPath);
@@ -32,7 +38,7 @@ export default function ProjectFilesFilepathDiff() {
   );
 
   const isLoading = isLoadingDiff || (diffError && isLoadingFile);
-  const content = diffContent || fileContent || null;
+  const hasDiff = !!diffFiles && diffFiles.length > 0;
 
   const handleCloseFile = () => {
     if (!decodedPath) {
@@ -81,7 +87,7 @@ export default function ProjectFilesFilepathDiff() {
     );
   }
 
-  if (!(decodedPath && content)) {
+  if (!(decodedPath && (hasDiff || fileContent))) {
     return (
       <div className="flex h-full flex-1 items-center justify-center">
         <div className="text-muted-foreground text-sm">No diff available</div>
@@ -92,7 +98,13 @@ export default function ProjectFilesFilepathDiff() {
   return (
     <ContextMenu>
       <ContextMenuTrigger
-        render={<DiffViewer diffContent={content} filePath={decodedPath} />}
+        render={
+          <DiffViewer
+            content={fileContent ?? undefined}
+            filePath={decodedPath}
+            files={diffFiles ?? NO_FILES}
+          />
+        }
       />
       <ContextMenuPopup align="start">
         <ContextMenuItem onClick={handleCloseFile}>